@@ -0,0 +1,172 @@
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::headers::SectionHeader;
+use crate::parser::PeFile;
+
+/// Alignment the Windows loader applies to `pointer_to_raw_data` whenever the
+/// declared file alignment is at least this large.
+const LOADER_RAW_ALIGNMENT: u64 = 0x200;
+/// Smallest section alignment the loader honours; below it the file alignment is used.
+const PAGE_SIZE: u64 = 0x1000;
+
+/// Where an RVA ends up once the image is mapped by the loader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Mapping {
+    /// Backed by real file data at `offset`.
+    /// `section` is the section index, or `None` for the headers region.
+    File { offset: usize, section: Option<usize> },
+    /// Inside section `section`'s virtual extent but past its raw data;
+    /// the loader fills this space with zeros.
+    ZeroFill { section: usize },
+}
+
+impl PeFile<'_> {
+    /// Translate an RVA the way the Windows loader maps the image.
+    ///
+    /// Section raw pointers are rounded down to 512 bytes (when the file
+    /// alignment allows it), raw sizes are rounded up to the file alignment and
+    /// capped by the section's virtual size, and anything past the raw data but
+    /// inside the virtual size is reported as [`Mapping::ZeroFill`]. RVAs inside
+    /// the headers map 1:1 to file offsets.
+    ///
+    /// Returns `None` if the RVA is outside every section and the headers, or
+    /// if its backing data lies beyond the end of the file.
+    pub fn map_rva(&self, rva: u32) -> Option<Mapping> {
+        let rva = rva as u64;
+
+        for (index, section) in self.sections.iter().enumerate() {
+            let start = section.virtual_address as u64;
+            if rva < start || rva >= start + self.virtual_extent(section) {
+                continue;
+            }
+
+            let delta = rva - start;
+            if delta >= self.raw_extent(section) {
+                return Some(Mapping::ZeroFill { section: index });
+            }
+            let offset = self.raw_start(section) + delta;
+            return (offset < self.buffer.len() as u64)
+                .then_some(Mapping::File { offset: offset as usize, section: Some(index) });
+        }
+
+        let headers = self.optional_header.size_of_headers().min(self.buffer.len() as u64);
+        (rva < headers).then_some(Mapping::File { offset: rva as usize, section: None })
+    }
+
+    /// Convert an RVA to a file offset, if it is backed by file data.
+    pub fn rva_to_offset(&self, rva: u32) -> Option<usize> {
+        match self.map_rva(rva)? {
+            Mapping::File { offset, .. } => Some(offset),
+            Mapping::ZeroFill { .. } => None,
+        }
+    }
+
+    /// Convert a file offset back to the RVA it is mapped at.
+    ///
+    /// Returns `None` for offsets that aren't mapped into memory (e.g. the
+    /// overlay or file-alignment padding past a section's virtual size).
+    pub fn offset_to_rva(&self, offset: usize) -> Option<u32> {
+        let offset = offset as u64;
+        if offset >= self.buffer.len() as u64 {
+            return None;
+        }
+
+        for section in &self.sections {
+            let raw_start = self.raw_start(section);
+            if offset >= raw_start && offset < raw_start + self.raw_extent(section) {
+                return u32::try_from(section.virtual_address as u64 + (offset - raw_start)).ok();
+            }
+        }
+
+        (offset < self.optional_header.size_of_headers()).then_some(offset as u32)
+    }
+
+    /// Convert an RVA to a virtual address using the preferred `image_base`.
+    pub fn rva_to_va(&self, rva: u32) -> u64 {
+        self.optional_header.image_base().wrapping_add(rva as u64)
+    }
+
+    /// Convert a virtual address to an RVA, if it lies within 4 GiB above `image_base`.
+    pub fn va_to_rva(&self, va: u64) -> Option<u32> {
+        va.checked_sub(self.optional_header.image_base())
+            .and_then(|rva| u32::try_from(rva).ok())
+    }
+
+    /// Convert a virtual address straight to a file offset.
+    pub fn va_to_offset(&self, va: u64) -> Option<usize> {
+        self.rva_to_offset(self.va_to_rva(va)?)
+    }
+
+    /// Convert a file offset to the virtual address it is loaded at.
+    pub fn offset_to_va(&self, offset: usize) -> Option<u64> {
+        self.offset_to_rva(offset).map(|rva| self.rva_to_va(rva))
+    }
+
+    /// Index of the section whose virtual extent contains `rva`.
+    pub fn section_index_for_rva(&self, rva: u32) -> Option<usize> {
+        self.sections.iter().position(|section| {
+            let start = section.virtual_address as u64;
+            (rva as u64) >= start && (rva as u64) < start + self.virtual_extent(section)
+        })
+    }
+
+    /// Section whose virtual extent contains `rva`.
+    pub fn section_for_rva(&self, rva: u32) -> Option<&SectionHeader> {
+        self.section_index_for_rva(rva).map(|index| &self.sections[index])
+    }
+
+    /// Raw data of `section` as stored in the file, starting where the loader
+    /// reads it from and cut short at the end of the file.
+    pub fn section_data(&self, section: &SectionHeader) -> &[u8] {
+        &self.buffer[self.section_raw_range(section)]
+    }
+
+    /// File range backing [`PeFile::section_data`].
+    pub(crate) fn section_raw_range(&self, section: &SectionHeader) -> Range<usize> {
+        let start = (self.raw_start(section) as usize).min(self.buffer.len());
+        let end = start.saturating_add(section.size_of_raw_data as usize).min(self.buffer.len());
+        start..end
+    }
+
+    /// Section alignment as the loader applies it.
+    fn section_alignment(&self) -> u64 {
+        let alignment = self.optional_header.section_alignment();
+        let alignment = if alignment < PAGE_SIZE { self.optional_header.file_alignment() } else { alignment };
+        alignment.max(1)
+    }
+
+    /// File offset the loader actually reads a section's raw data from.
+    fn raw_start(&self, section: &SectionHeader) -> u64 {
+        let pointer = section.pointer_to_raw_data as u64;
+        if self.optional_header.file_alignment() >= LOADER_RAW_ALIGNMENT {
+            pointer & !(LOADER_RAW_ALIGNMENT - 1)
+        } else {
+            pointer
+        }
+    }
+
+    /// Virtual size, falling back to the raw size when it is zero.
+    fn virtual_size(section: &SectionHeader) -> u64 {
+        match section.virtual_size {
+            0 => section.size_of_raw_data as u64,
+            size => size as u64,
+        }
+    }
+
+    /// Number of bytes the section occupies in memory.
+    fn virtual_extent(&self, section: &SectionHeader) -> u64 {
+        align_up(Self::virtual_size(section), self.section_alignment())
+    }
+
+    /// Number of bytes of the section that come from the file.
+    fn raw_extent(&self, section: &SectionHeader) -> u64 {
+        let file_alignment = self.optional_header.file_alignment().max(1);
+        align_up(section.size_of_raw_data as u64, file_alignment).min(self.virtual_extent(section))
+    }
+}
+
+fn align_up(value: u64, alignment: u64) -> u64 {
+    value.div_ceil(alignment) * alignment
+}
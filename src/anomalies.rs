@@ -0,0 +1,239 @@
+use serde::Serialize;
+
+use crate::bound_import_table::parse_bound_imports;
+use crate::certificate_table::parse_certificate_table;
+use crate::debug_directory::parse_debug_directory;
+use crate::delay_import_table::parse_delay_imports;
+use crate::dotnet::parse_dotnet;
+use crate::exception_table::parse_exception_table;
+use crate::export_table::parse_exports;
+use crate::headers::FileHeader;
+use crate::import_table::parse_imports;
+use crate::load_config::parse_load_config;
+use crate::parser::PeFile;
+use crate::relocation_table::parse_relocation_table;
+use crate::resource_table::parse_resource_table;
+use crate::tls_directory::parse_tls_directory;
+use crate::utils::rva_to_offset;
+
+/// How bad an anomaly is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Severity {
+    /// Unusual but harmless (e.g. padding or tooling quirks).
+    Info,
+    /// Structure the Windows loader tolerates but well-formed files don't have.
+    Warning,
+    /// Structure that strict parsing rejects or that breaks part of the file.
+    Error,
+}
+
+/// What kind of problem was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum AnomalyKind {
+    /// DOS header doesn't start with `MZ`.
+    BadDosMagic,
+    /// `PE\0\0` signature missing at `e_lfanew`.
+    BadPeSignature,
+    /// Optional header magic is neither PE32 nor PE32+.
+    UnsupportedOptionalHeader,
+    /// `size_of_optional_header` doesn't match the header that was read.
+    OptionalHeaderSizeMismatch,
+    /// Fewer section headers fit in the file than `number_of_sections` declares.
+    TruncatedSectionTable,
+    /// Two sections claim the same virtual address range.
+    OverlappingSections,
+    /// A section's raw data extends past the end of the file.
+    SectionOutsideFile,
+    /// The entry point doesn't fall inside any section.
+    EntryPointOutsideSections,
+    /// A data directory points at an address that doesn't map to file data.
+    UnmappedDirectory,
+    /// A table was present but could not be parsed.
+    MalformedTable,
+    /// The stored image checksum doesn't match the file contents.
+    ChecksumMismatch,
+}
+
+/// A single structural problem found while parsing.
+#[derive(Debug, Clone, Serialize)]
+pub struct Anomaly {
+    pub severity: Severity,
+    pub kind: AnomalyKind,
+    /// File offset the anomaly refers to, when there is one.
+    pub offset: Option<usize>,
+    /// Human-readable details.
+    pub message: String,
+}
+
+impl Anomaly {
+    pub fn new(severity: Severity, kind: AnomalyKind, offset: Option<usize>, message: impl Into<String>) -> Self {
+        Anomaly { severity, kind, offset, message: message.into() }
+    }
+}
+
+/// Names of the 16 data directory slots, by index.
+pub const DIRECTORY_NAMES: [&str; 16] = [
+    "EXPORT", "IMPORT", "RESOURCE", "EXCEPTION", "SECURITY", "BASERELOC", "DEBUG", "ARCHITECTURE",
+    "GLOBALPTR", "TLS", "LOAD_CONFIG", "BOUND_IMPORT", "IAT", "DELAY_IMPORT", "COM_DESCRIPTOR", "RESERVED",
+];
+
+impl PeFile<'_> {
+    /// Run structural sanity checks over an already parsed file.
+    ///
+    /// This never fails: anything odd is reported as an [`Anomaly`], ordered
+    /// by where it was found (headers, sections, then data directories).
+    pub fn anomalies(&self) -> Vec<Anomaly> {
+        let mut anomalies = Vec::new();
+        self.check_optional_header_size(&mut anomalies);
+        self.check_sections(&mut anomalies);
+        self.check_entry_point(&mut anomalies);
+        self.check_checksum(&mut anomalies);
+        self.check_directories(&mut anomalies);
+        anomalies
+    }
+
+    fn check_optional_header_size(&self, anomalies: &mut Vec<Anomaly>) {
+        let declared = self.file_header.size_of_optional_header as usize;
+        let expected = self.optional_header.expected_size();
+        if declared != expected {
+            // size_of_optional_header sits 16 bytes into the FileHeader
+            let offset = self.e_lfanew + 4 + 16;
+            anomalies.push(Anomaly::new(
+                Severity::Warning,
+                AnomalyKind::OptionalHeaderSizeMismatch,
+                Some(offset),
+                format!("size_of_optional_header is {declared}, header with its data directories is {expected} bytes"),
+            ));
+        }
+    }
+
+    fn check_sections(&self, anomalies: &mut Vec<Anomaly>) {
+        let table = self.e_lfanew + 4 + FileHeader::SIZE + self.file_header.size_of_optional_header as usize;
+
+        for (i, section) in self.sections.iter().enumerate() {
+            let header_offset = table + i * 40;
+            let raw_end = section.pointer_to_raw_data as u64 + section.size_of_raw_data as u64;
+            if section.size_of_raw_data != 0 && raw_end > self.buffer.len() as u64 {
+                anomalies.push(Anomaly::new(
+                    Severity::Warning,
+                    AnomalyKind::SectionOutsideFile,
+                    Some(header_offset),
+                    format!("section {} raw data ends at 0x{raw_end:X}, file is 0x{:X} bytes", section.name_string(), self.buffer.len()),
+                ));
+            }
+        }
+
+        // Sweep in address order, reporting each section at most once against
+        // the earlier section reaching furthest, so hostile tables stay linear.
+        let mut order: Vec<usize> = (0..self.sections.len()).collect();
+        order.sort_by_key(|&i| self.sections[i].virtual_address);
+        let mut furthest: Option<(usize, u64, u64)> = None;
+        for i in order {
+            let section = &self.sections[i];
+            let start = section.virtual_address as u64;
+            let end = start + section.virtual_size.max(section.size_of_raw_data) as u64;
+            if let Some((prev, prev_start, prev_end)) = furthest {
+                if start < prev_end && prev_start < end {
+                    anomalies.push(Anomaly::new(
+                        Severity::Warning,
+                        AnomalyKind::OverlappingSections,
+                        Some(table + i * 40),
+                        format!("sections {} and {} overlap in memory", self.sections[prev].name_string(), section.name_string()),
+                    ));
+                }
+            }
+            if !matches!(furthest, Some((_, _, prev_end)) if end <= prev_end) {
+                furthest = Some((i, start, end));
+            }
+        }
+    }
+
+    fn check_entry_point(&self, anomalies: &mut Vec<Anomaly>) {
+        let entry = self.optional_header.address_of_entry_point();
+        // DLLs without DllMain legitimately have no entry point.
+        if entry == 0 {
+            return;
+        }
+        let inside = self.sections.iter().any(|section| {
+            let start = section.virtual_address as u64;
+            entry >= start && entry < start + section.virtual_size.max(section.size_of_raw_data) as u64
+        });
+        if !inside {
+            anomalies.push(Anomaly::new(
+                Severity::Warning,
+                AnomalyKind::EntryPointOutsideSections,
+                None,
+                format!("entry point 0x{entry:X} is not inside any section"),
+            ));
+        }
+    }
+
+    fn check_checksum(&self, anomalies: &mut Vec<Anomaly>) {
+        let checksum = self.checksum();
+        // Linkers leave user-mode checksums at zero unless asked; only drivers must have one.
+        if checksum.valid || (checksum.stored == 0 && !checksum.enforced) {
+            return;
+        }
+        let severity = if checksum.enforced { Severity::Error } else { Severity::Info };
+        anomalies.push(Anomaly::new(
+            severity,
+            AnomalyKind::ChecksumMismatch,
+            Some(self.checksum_offset()),
+            format!("checksum is 0x{:08X}, file sums to 0x{:08X}", checksum.stored, checksum.computed),
+        ));
+    }
+
+    fn check_directories(&self, anomalies: &mut Vec<Anomaly>) {
+        for (index, directory) in self.optional_header.data_directory().iter().enumerate() {
+            if directory.virtual_address == 0 || directory.size == 0 {
+                continue;
+            }
+
+            // The security directory holds a file offset, not an RVA.
+            let mapped = if index == 4 {
+                directory.virtual_address as u64 + directory.size as u64 <= self.buffer.len() as u64
+            } else {
+                rva_to_offset(self, directory.virtual_address).is_some()
+            };
+            if !mapped {
+                anomalies.push(Anomaly::new(
+                    Severity::Warning,
+                    AnomalyKind::UnmappedDirectory,
+                    None,
+                    format!("{} directory at 0x{:X} does not map to file data", DIRECTORY_NAMES[index], directory.virtual_address),
+                ));
+                continue;
+            }
+
+            // Every directory with a parser, so nothing `pretty_json` drops goes unreported.
+            let error = match index {
+                0 => parse_exports(self).err(),
+                1 => parse_imports(self).err(),
+                2 => parse_resource_table(self).err(),
+                3 => parse_exception_table(self).err(),
+                4 => parse_certificate_table(self).err(),
+                5 => parse_relocation_table(self).err(),
+                6 => parse_debug_directory(self).err(),
+                9 => parse_tls_directory(self).err(),
+                10 => parse_load_config(self).err(),
+                11 => parse_bound_imports(self).err(),
+                13 => parse_delay_imports(self).err(),
+                14 => parse_dotnet(self).err(),
+                _ => None,
+            };
+            if let Some(error) = error {
+                let offset = if index == 4 {
+                    Some(directory.virtual_address as usize)
+                } else {
+                    rva_to_offset(self, directory.virtual_address)
+                };
+                anomalies.push(Anomaly::new(
+                    Severity::Warning,
+                    AnomalyKind::MalformedTable,
+                    offset,
+                    format!("{} directory could not be parsed: {error}", DIRECTORY_NAMES[index]),
+                ));
+            }
+        }
+    }
+}
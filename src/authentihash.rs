@@ -0,0 +1,160 @@
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use crate::certificate_table::parse_certificate_table;
+use crate::errors::Result;
+use crate::headers::FileHeader;
+use crate::parser::PeFile;
+use crate::pkcs7::SignedData;
+use crate::utils::read_bytes;
+
+/// Digest algorithms supported for the Authenticode image hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha256,
+}
+
+impl HashAlgorithm {
+    /// Match a digest algorithm name as reported by [`crate::pkcs7`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sha1" => Some(HashAlgorithm::Sha1),
+            "sha256" => Some(HashAlgorithm::Sha256),
+            _ => None,
+        }
+    }
+}
+
+/// Result of comparing one signature's embedded digest with the file.
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestCheck {
+    pub digest_algorithm: String,
+    /// Digest from the signature's `SpcIndirectDataContent`, as uppercase hex.
+    pub signed_digest: String,
+    /// Digest computed from the file; `None` for unsupported algorithms.
+    pub computed_digest: Option<String>,
+    /// Whether the two digests are equal.
+    pub matches: bool,
+}
+
+impl PeFile<'_> {
+    /// Compute the Authenticode image hash, as uppercase hex.
+    ///
+    /// Follows the Authenticode specification: the headers are hashed without
+    /// the `checksum` field and the security directory entry, then every
+    /// section's raw data in file order, then any data after the last section
+    /// except the attribute certificate table itself.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidSize` if the headers or a section's raw data run
+    /// past the end of the file.
+    pub fn authentihash(&self, algorithm: HashAlgorithm) -> Result<String> {
+        let digest = match algorithm {
+            HashAlgorithm::Sha1 => self.image_digest::<Sha1>()?,
+            HashAlgorithm::Sha256 => self.image_digest::<Sha256>()?,
+        };
+        Ok(digest.iter().map(|b| format!("{b:02X}")).collect())
+    }
+
+    /// Compare every embedded Authenticode digest (including nested
+    /// signatures) with the hash of the file.
+    ///
+    /// Only the digest is checked; signatures and certificate chains are not
+    /// cryptographically verified.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidTableOffset` if the file has no certificate
+    /// table, otherwise the errors of [`PeFile::authentihash`].
+    pub fn verify_authentihash(&self) -> Result<Vec<DigestCheck>> {
+        let entries = parse_certificate_table(self)?;
+        let mut signatures = Vec::new();
+        for signature in entries.iter().filter_map(|entry| entry.signature.as_ref()) {
+            collect_signatures(signature, &mut signatures);
+        }
+
+        signatures
+            .iter()
+            .filter_map(|signature| signature.indirect_data.as_ref())
+            .map(|indirect| {
+                let computed_digest = match HashAlgorithm::from_name(&indirect.digest_algorithm) {
+                    Some(algorithm) => Some(self.authentihash(algorithm)?),
+                    None => None,
+                };
+                Ok(DigestCheck {
+                    digest_algorithm: indirect.digest_algorithm.clone(),
+                    signed_digest: indirect.digest.clone(),
+                    matches: computed_digest.as_deref() == Some(indirect.digest.as_str()),
+                    computed_digest,
+                })
+            })
+            .collect()
+    }
+
+    fn image_digest<D: Digest>(&self) -> Result<Vec<u8>> {
+        let optional_header = self.e_lfanew + 4 + FileHeader::SIZE;
+        let checksum = self.checksum_offset();
+        let directories = optional_header + if self.optional_header.is_64() { 112 } else { 96 };
+
+        // Byte ranges left out of the hash, in file order.
+        let mut excluded = vec![(checksum, checksum + 4)];
+        if self.optional_header.number_of_rva_and_sizes() > 4 {
+            excluded.push((directories + 4 * 8, directories + 5 * 8));
+        }
+        let security_dir = self.optional_header.data_directory()[4];
+        let certificates = (security_dir.virtual_address != 0).then(|| {
+            let start = security_dir.virtual_address as usize;
+            (start, start + security_dir.size as usize)
+        });
+        excluded.extend(certificates);
+        excluded.sort_unstable();
+
+        let mut hasher = D::new();
+        let mut hash = |start: usize, end: usize| -> Result<()> {
+            read_bytes(&self.buffer, start, end.saturating_sub(start))?;
+            let mut pos = start;
+            for &(skip_start, skip_end) in &excluded {
+                if skip_end <= pos || skip_start >= end {
+                    continue;
+                }
+                if skip_start > pos {
+                    hasher.update(&self.buffer[pos..skip_start]);
+                }
+                pos = pos.max(skip_end);
+            }
+            if pos < end {
+                hasher.update(&self.buffer[pos..end]);
+            }
+            Ok(())
+        };
+
+        let size_of_headers = self.optional_header.size_of_headers() as usize;
+        hash(0, size_of_headers)?;
+
+        let mut sections: Vec<_> = self.sections.iter().filter(|section| section.size_of_raw_data != 0).collect();
+        sections.sort_by_key(|section| section.pointer_to_raw_data);
+        let mut hashed_end = size_of_headers;
+        for section in sections {
+            let start = section.pointer_to_raw_data as usize;
+            let end = start + section.size_of_raw_data as usize;
+            hash(start, end)?;
+            hashed_end = hashed_end.max(end);
+        }
+
+        // Trailing data (overlay), minus the certificate table.
+        if hashed_end < self.buffer.len() {
+            hash(hashed_end, self.buffer.len())?;
+        }
+
+        Ok(hasher.finalize().to_vec())
+    }
+}
+
+/// `signature` followed by its nested signatures, depth first.
+fn collect_signatures<'a>(signature: &'a SignedData, out: &mut Vec<&'a SignedData>) {
+    out.push(signature);
+    for nested in signature.signers.iter().flat_map(|signer| &signer.nested_signatures) {
+        collect_signatures(nested, out);
+    }
+}
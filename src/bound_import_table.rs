@@ -0,0 +1,107 @@
+use serde::Serialize;
+
+use crate::errors::{Error, Result};
+use crate::import_table::parse_imports;
+use crate::parser::PeFile;
+use crate::utils::{read_bytes, read_c_string, read_u16, read_u32, rva_to_offset};
+
+/// Size of both `IMAGE_BOUND_IMPORT_DESCRIPTOR` and `IMAGE_BOUND_FORWARDER_REF`.
+const ENTRY_SIZE: usize = 8;
+/// `time_date_stamp` of an import descriptor bound through the bound import directory.
+const NEW_STYLE_BINDING: u32 = 0xFFFF_FFFF;
+
+/// `IMAGE_BOUND_FORWARDER_REF`: a DLL the bound module forwards into.
+#[derive(Debug, Serialize)]
+pub struct BoundForwarderRef {
+    pub time_date_stamp: u32,
+    /// Offset of the name from the start of the bound import directory.
+    pub offset_module_name: u16,
+    pub module_name: String,
+    pub reserved: u16,
+}
+
+/// `IMAGE_BOUND_IMPORT_DESCRIPTOR` with its forwarder references.
+#[derive(Debug, Serialize)]
+pub struct BoundImport {
+    /// Timestamp of the DLL the imports were bound against.
+    pub time_date_stamp: u32,
+    /// Offset of the name from the start of the bound import directory.
+    pub offset_module_name: u16,
+    pub module_name: String,
+    pub forwarders: Vec<BoundForwarderRef>,
+    /// `time_date_stamp` of the matching regular import descriptor, if any.
+    pub import_time_date_stamp: Option<u32>,
+    /// Whether the matching import descriptor agrees with this binding: its
+    /// timestamp is `0xFFFFFFFF` (new-style binding) or equal to ours.
+    pub consistent: bool,
+}
+
+/// Parse the bound import directory (data directory 11).
+///
+/// Module names are stored as offsets from the start of the directory, not
+/// as RVAs. Each entry is matched by name (case-insensitively) against the
+/// regular import descriptors to report whether the binding is still consistent.
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if the directory is absent, does not map
+/// into the file, or a module name offset falls outside it.
+pub fn parse_bound_imports(pe: &PeFile) -> Result<Vec<BoundImport>> {
+    let bound_dir = pe.optional_header.data_directory()[11];
+    if bound_dir.virtual_address == 0 {
+        return Err(Error::InvalidTableOffset);
+    }
+    let offset = rva_to_offset(pe, bound_dir.virtual_address)
+        .ok_or(Error::InvalidTableOffset)?;
+    let slice = read_bytes(&pe.buffer, offset, bound_dir.size as usize)?;
+
+    let module_name = |offset_module_name: u16| {
+        if offset_module_name as u32 >= bound_dir.size {
+            return Err(Error::InvalidTableOffset);
+        }
+        read_c_string(pe, bound_dir.virtual_address + offset_module_name as u32)
+    };
+    let imports = parse_imports(pe).unwrap_or_default();
+
+    let mut entries = Vec::new();
+    let mut pos = 0;
+
+    // Iterate descriptors until an all-zero terminator; forwarder refs follow each one.
+    while pos + ENTRY_SIZE <= slice.len() {
+        let time_date_stamp    = read_u32(slice, pos)?;
+        let offset_module_name = read_u16(slice, pos + 4)?;
+        let forwarder_count    = read_u16(slice, pos + 6)?;
+        if time_date_stamp == 0 && offset_module_name == 0 && forwarder_count == 0 {
+            break;
+        }
+        pos += ENTRY_SIZE;
+
+        let mut forwarders = Vec::with_capacity(forwarder_count as usize);
+        for _ in 0..forwarder_count {
+            let offset_module_name = read_u16(slice, pos + 4)?;
+            forwarders.push(BoundForwarderRef {
+                time_date_stamp: read_u32(slice, pos)?,
+                offset_module_name,
+                module_name: module_name(offset_module_name)?,
+                reserved: read_u16(slice, pos + 6)?,
+            });
+            pos += ENTRY_SIZE;
+        }
+
+        let module_name = module_name(offset_module_name)?;
+        let import_time_date_stamp = imports
+            .iter()
+            .find(|dll| dll.dll_name.eq_ignore_ascii_case(&module_name))
+            .map(|dll| dll.descriptor.time_date_stamp);
+
+        entries.push(BoundImport {
+            time_date_stamp,
+            offset_module_name,
+            module_name,
+            forwarders,
+            import_time_date_stamp,
+            consistent: matches!(import_time_date_stamp, Some(stamp) if stamp == NEW_STYLE_BINDING || stamp == time_date_stamp),
+        });
+    }
+
+    Ok(entries)
+}
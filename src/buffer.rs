@@ -0,0 +1,61 @@
+use std::ops::Deref;
+
+/// Bytes backing a [`crate::PeFile`].
+///
+/// Parsing only ever needs a `&[u8]`, so the image can live on the heap, in a
+/// caller-owned slice, or (with the `mmap` feature) in a memory-mapped file
+/// without being copied.
+pub enum PeBuffer<'a> {
+    /// Buffer owned by the `PeFile` (e.g. read from disk).
+    Owned(Vec<u8>),
+    /// Slice borrowed from the caller; nothing is copied.
+    Borrowed(&'a [u8]),
+    /// Read-only memory map of a file on disk.
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl PeBuffer<'_> {
+    /// Copy borrowed bytes so the buffer no longer depends on the caller's lifetime.
+    ///
+    /// Owned and memory-mapped buffers are moved as they are.
+    pub fn into_owned(self) -> PeBuffer<'static> {
+        match self {
+            PeBuffer::Owned(vec) => PeBuffer::Owned(vec),
+            PeBuffer::Borrowed(slice) => PeBuffer::Owned(slice.to_vec()),
+            #[cfg(feature = "mmap")]
+            PeBuffer::Mapped(map) => PeBuffer::Mapped(map),
+        }
+    }
+}
+
+impl Deref for PeBuffer<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            PeBuffer::Owned(vec) => vec,
+            PeBuffer::Borrowed(slice) => slice,
+            #[cfg(feature = "mmap")]
+            PeBuffer::Mapped(map) => map,
+        }
+    }
+}
+
+impl AsRef<[u8]> for PeBuffer<'_> {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl std::fmt::Debug for PeBuffer<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            PeBuffer::Owned(_) => "Owned",
+            PeBuffer::Borrowed(_) => "Borrowed",
+            #[cfg(feature = "mmap")]
+            PeBuffer::Mapped(_) => "Mapped",
+        };
+        write!(f, "PeBuffer::{kind}({} bytes)", self.len())
+    }
+}
@@ -0,0 +1,92 @@
+use serde::Serialize;
+
+use crate::errors::{Error, Result};
+use crate::parser::PeFile;
+use crate::pkcs7::{parse_signed_data, SignedData};
+use crate::utils::{read_bytes, read_u16, read_u32};
+
+/// Size of the `WIN_CERTIFICATE` header preceding the certificate data.
+const HEADER_SIZE: usize = 8;
+const WIN_CERT_TYPE_PKCS_SIGNED_DATA: u16 = 0x0002;
+
+/// One `WIN_CERTIFICATE` entry from the attribute certificate table.
+#[derive(Debug, Clone, Serialize)]
+pub struct WinCertificate {
+    /// File offset of the entry.
+    pub offset: usize,
+    /// `dwLength`: header plus certificate data, before 8-byte padding.
+    pub length: u32,
+    pub revision: u16,
+    /// Readable form of `revision`.
+    pub revision_name: &'static str,
+    pub certificate_type: u16,
+    /// Readable form of `certificate_type`.
+    pub type_name: &'static str,
+    /// Decoded Authenticode signature, for `PKCS_SIGNED_DATA` entries.
+    pub signature: Option<SignedData>,
+}
+
+impl WinCertificate {
+    /// Borrow the raw certificate data that follows the header.
+    pub fn data<'a>(&self, pe: &'a PeFile) -> Result<&'a [u8]> {
+        read_bytes(&pe.buffer, self.offset + HEADER_SIZE, (self.length as usize).saturating_sub(HEADER_SIZE))
+    }
+}
+
+/// Parse the attribute certificate table (data directory 4).
+///
+/// Unlike every other directory, its address is a file offset rather than an
+/// RVA, because the table is not mapped into memory. Entries are padded to
+/// 8-byte boundaries.
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if the directory is absent,
+/// `Error::InvalidSize` if it runs past the end of the file, or
+/// `Error::InvalidData` if an entry declares a length shorter than its header.
+pub fn parse_certificate_table(pe: &PeFile) -> Result<Vec<WinCertificate>> {
+    let security_dir = pe.optional_header.data_directory()[4];
+    if security_dir.virtual_address == 0 || security_dir.size == 0 {
+        return Err(Error::InvalidTableOffset);
+    }
+    let start = security_dir.virtual_address as usize;
+    let table = read_bytes(&pe.buffer, start, security_dir.size as usize)?;
+
+    let mut certificates = Vec::new();
+    let mut pos = 0;
+    while pos + HEADER_SIZE <= table.len() {
+        let length = read_u32(table, pos)?;
+        if (length as usize) < HEADER_SIZE {
+            return Err(Error::InvalidData);
+        }
+        let revision = read_u16(table, pos + 4)?;
+        let certificate_type = read_u16(table, pos + 6)?;
+        let data = read_bytes(table, pos + HEADER_SIZE, length as usize - HEADER_SIZE)?;
+
+        certificates.push(WinCertificate {
+            offset: start + pos,
+            length,
+            revision,
+            revision_name: match revision {
+                0x0100 => "REVISION_1_0",
+                0x0200 => "REVISION_2_0",
+                _ => "UNKNOWN",
+            },
+            certificate_type,
+            type_name: match certificate_type {
+                0x0001 => "X509",
+                WIN_CERT_TYPE_PKCS_SIGNED_DATA => "PKCS_SIGNED_DATA",
+                0x0003 => "RESERVED_1",
+                0x0004 => "TS_STACK_SIGNED",
+                _ => "UNKNOWN",
+            },
+            signature: match certificate_type {
+                WIN_CERT_TYPE_PKCS_SIGNED_DATA => parse_signed_data(data).ok(),
+                _ => None,
+            },
+        });
+
+        pos += (length as usize).div_ceil(8) * 8;
+    }
+
+    Ok(certificates)
+}
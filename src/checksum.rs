@@ -0,0 +1,66 @@
+use serde::Serialize;
+
+use crate::headers::FileHeader;
+use crate::parser::PeFile;
+
+const IMAGE_SUBSYSTEM_NATIVE: u64 = 1;
+const IMAGE_DLLCHARACTERISTICS_WDM_DRIVER: u64 = 0x2000;
+
+/// Stored versus computed image checksum.
+#[derive(Debug, Clone, Serialize)]
+pub struct Checksum {
+    /// `checksum` field of the optional header.
+    pub stored: u32,
+    /// Value `CheckSumMappedFile` would produce for the file.
+    pub computed: u32,
+    /// Whether `stored` equals `computed`.
+    pub valid: bool,
+    /// Native subsystem or WDM driver: the kernel refuses to load these with a
+    /// wrong checksum, while user-mode images ignore it.
+    pub enforced: bool,
+}
+
+impl PeFile<'_> {
+    /// File offset of the optional header's `checksum` field.
+    pub(crate) fn checksum_offset(&self) -> usize {
+        self.e_lfanew + 4 + FileHeader::SIZE + 64
+    }
+
+    /// Compute the image checksum the way `CheckSumMappedFile` does.
+    ///
+    /// The file is summed as little-endian 16-bit words with the carry folded
+    /// back in, skipping the checksum field itself, then the file length is
+    /// added.
+    pub fn compute_checksum(&self) -> u32 {
+        let skip = self.checksum_offset();
+        let mut sum = 0u64;
+        for (index, chunk) in self.buffer.chunks(2).enumerate() {
+            let offset = index * 2;
+            if offset >= skip && offset < skip + 4 {
+                continue;
+            }
+            let word = match chunk {
+                [low, high] => u16::from_le_bytes([*low, *high]),
+                [low] => *low as u16,
+                _ => unreachable!(),
+            };
+            sum += word as u64;
+            sum = (sum & 0xFFFF) + (sum >> 16);
+        }
+        sum = (sum & 0xFFFF) + (sum >> 16);
+        (sum as u32).wrapping_add(self.buffer.len() as u32)
+    }
+
+    /// Compare the stored checksum with [`PeFile::compute_checksum`].
+    pub fn checksum(&self) -> Checksum {
+        let stored = self.optional_header.checksum() as u32;
+        let computed = self.compute_checksum();
+        Checksum {
+            stored,
+            computed,
+            valid: stored == computed,
+            enforced: self.optional_header.subsystem() == IMAGE_SUBSYSTEM_NATIVE
+                || self.optional_header.dll_characteristics() & IMAGE_DLLCHARACTERISTICS_WDM_DRIVER != 0,
+        }
+    }
+}
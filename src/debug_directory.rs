@@ -0,0 +1,334 @@
+use serde::Serialize;
+
+use crate::errors::{Error, Result};
+use crate::parser::PeFile;
+use crate::utils::{read_bytes, read_u32, rva_to_offset, ByteReader};
+
+/// Size of one `IMAGE_DEBUG_DIRECTORY` entry.
+const ENTRY_SIZE: usize = 28;
+
+const IMAGE_DEBUG_TYPE_CODEVIEW: u32 = 2;
+const IMAGE_DEBUG_TYPE_VC_FEATURE: u32 = 12;
+const IMAGE_DEBUG_TYPE_POGO: u32 = 13;
+const IMAGE_DEBUG_TYPE_REPRO: u32 = 16;
+const IMAGE_DEBUG_TYPE_EMBEDDED_PORTABLE_PDB: u32 = 17;
+const IMAGE_DEBUG_TYPE_EX_DLLCHARACTERISTICS: u32 = 20;
+
+/// CodeView record pointing at the PDB (`RSDS` for PDB 7.0, `NB10` for PDB 2.0).
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeView {
+    /// `"RSDS"` or `"NB10"`.
+    pub signature: String,
+    /// PDB GUID as `XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX` (RSDS only).
+    pub guid: Option<String>,
+    /// PDB timestamp signature (NB10 only).
+    pub timestamp: Option<u32>,
+    pub age: u32,
+    /// Path of the PDB as recorded by the linker.
+    pub path: String,
+    /// Symbol server path (`name.pdb/<GUID><age>/name.pdb`).
+    pub symbol_store_path: String,
+}
+
+/// One section contribution listed in a POGO record.
+#[derive(Debug, Clone, Serialize)]
+pub struct PogoEntry {
+    pub rva: u32,
+    pub size: u32,
+    pub name: String,
+}
+
+/// Profile-guided optimisation record listing section contributions.
+#[derive(Debug, Clone, Serialize)]
+pub struct Pogo {
+    /// Signature such as `"PGU"`, `"PGI"` or `"LTCG"`.
+    pub signature: String,
+    pub entries: Vec<PogoEntry>,
+}
+
+/// Counters of compiler security features (`VC_FEATURE`).
+#[derive(Debug, Clone, Serialize)]
+pub struct VcFeature {
+    pub pre_vc11: u32,
+    pub c_cpp: u32,
+    pub gs: u32,
+    pub sdl: u32,
+    pub guard_n: u32,
+}
+
+/// Hash of a reproducible build (`REPRO`); empty when the timestamp is the hash.
+#[derive(Debug, Clone, Serialize)]
+pub struct Repro {
+    pub hash: String,
+}
+
+/// Extended DLL characteristics (`EX_DLLCHARACTERISTICS`), mostly CET flags.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExDllCharacteristics {
+    pub flags: u32,
+    pub names: Vec<&'static str>,
+}
+
+/// Header of a compressed portable PDB embedded in the image (`MPDB`).
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddedPortablePdb {
+    pub uncompressed_size: u32,
+    /// File offset of the deflate-compressed PDB bytes.
+    pub compressed_offset: usize,
+    pub compressed_size: usize,
+}
+
+/// Decoded payload of a debug directory entry.
+#[derive(Debug, Clone, Serialize)]
+pub enum DebugPayload {
+    CodeView(CodeView),
+    Pogo(Pogo),
+    VcFeature(VcFeature),
+    Repro(Repro),
+    ExDllCharacteristics(ExDllCharacteristics),
+    EmbeddedPortablePdb(EmbeddedPortablePdb),
+}
+
+/// One `IMAGE_DEBUG_DIRECTORY` entry.
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugEntry {
+    pub characteristics: u32,
+    pub time_date_stamp: u32,
+    pub major_version: u16,
+    pub minor_version: u16,
+    /// Raw `IMAGE_DEBUG_TYPE_*` value.
+    pub kind: u32,
+    /// Readable form of `kind`.
+    pub type_name: &'static str,
+    pub size_of_data: u32,
+    pub address_of_raw_data: u32,
+    pub pointer_to_raw_data: u32,
+    /// Decoded payload, for the types we understand.
+    pub payload: Option<DebugPayload>,
+}
+
+impl DebugEntry {
+    /// Borrow the entry's raw payload bytes.
+    ///
+    /// Uses `pointer_to_raw_data` when set and falls back to `address_of_raw_data`.
+    pub fn data<'a>(&self, pe: &'a PeFile) -> Result<&'a [u8]> {
+        read_bytes(&pe.buffer, self.data_offset(pe)?, self.size_of_data as usize)
+    }
+
+    fn data_offset(&self, pe: &PeFile) -> Result<usize> {
+        if self.pointer_to_raw_data != 0 {
+            Ok(self.pointer_to_raw_data as usize)
+        } else {
+            rva_to_offset(pe, self.address_of_raw_data).ok_or(Error::InvalidTableOffset)
+        }
+    }
+}
+
+/// Readable name of an `IMAGE_DEBUG_TYPE_*` value.
+pub fn debug_type_name(kind: u32) -> &'static str {
+    match kind {
+        0 => "UNKNOWN",
+        1 => "COFF",
+        2 => "CODEVIEW",
+        3 => "FPO",
+        4 => "MISC",
+        5 => "EXCEPTION",
+        6 => "FIXUP",
+        7 => "OMAP_TO_SRC",
+        8 => "OMAP_FROM_SRC",
+        9 => "BORLAND",
+        10 => "RESERVED10",
+        11 => "CLSID",
+        12 => "VC_FEATURE",
+        13 => "POGO",
+        14 => "ILTCG",
+        15 => "MPX",
+        16 => "REPRO",
+        17 => "EMBEDDED_PORTABLE_PDB",
+        19 => "PDBCHECKSUM",
+        20 => "EX_DLLCHARACTERISTICS",
+        _ => "UNRECOGNIZED",
+    }
+}
+
+/// Parse the debug directory (data directory 6) and decode known payloads.
+///
+/// Payloads that can't be decoded leave `payload` as `None` rather than
+/// failing the whole directory.
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if the directory is absent or does not
+/// map into the file, or `Error::InvalidSize` if the entries are truncated.
+pub fn parse_debug_directory(pe: &PeFile) -> Result<Vec<DebugEntry>> {
+    let directory = pe.optional_header.data_directory()[6];
+    if directory.virtual_address == 0 {
+        return Err(Error::InvalidTableOffset);
+    }
+    let offset = rva_to_offset(pe, directory.virtual_address)
+        .ok_or(Error::InvalidTableOffset)?;
+    let count = directory.size as usize / ENTRY_SIZE;
+    read_bytes(&pe.buffer, offset, count * ENTRY_SIZE)?;
+
+    let mut r = ByteReader::new(&pe.buffer, offset);
+    let mut entries = Vec::with_capacity(count);
+    for _ in 0..count {
+        let mut entry = DebugEntry {
+            characteristics:     r.u32()?,
+            time_date_stamp:     r.u32()?,
+            major_version:       r.u16()?,
+            minor_version:       r.u16()?,
+            kind:                r.u32()?,
+            type_name:           "",
+            size_of_data:        r.u32()?,
+            address_of_raw_data: r.u32()?,
+            pointer_to_raw_data: r.u32()?,
+            payload:             None,
+        };
+        entry.type_name = debug_type_name(entry.kind);
+        entry.payload = entry.data(pe).ok().and_then(|data| decode_payload(&entry, data, pe));
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// Find the CodeView (PDB) record, if any.
+pub fn codeview(pe: &PeFile) -> Option<CodeView> {
+    parse_debug_directory(pe).ok()?.into_iter().find_map(|entry| match entry.payload {
+        Some(DebugPayload::CodeView(codeview)) => Some(codeview),
+        _ => None,
+    })
+}
+
+fn decode_payload(entry: &DebugEntry, data: &[u8], pe: &PeFile) -> Option<DebugPayload> {
+    match entry.kind {
+        IMAGE_DEBUG_TYPE_CODEVIEW => decode_codeview(data).map(DebugPayload::CodeView),
+        IMAGE_DEBUG_TYPE_POGO => decode_pogo(data).map(DebugPayload::Pogo),
+        IMAGE_DEBUG_TYPE_VC_FEATURE => Some(DebugPayload::VcFeature(VcFeature {
+            pre_vc11: read_u32(data, 0).ok()?,
+            c_cpp:    read_u32(data, 4).ok()?,
+            gs:       read_u32(data, 8).ok()?,
+            sdl:      read_u32(data, 12).ok()?,
+            guard_n:  read_u32(data, 16).ok()?,
+        })),
+        IMAGE_DEBUG_TYPE_REPRO => {
+            // Newer linkers store a length-prefixed hash; older ones store nothing.
+            let hash = match read_u32(data, 0) {
+                Ok(len) => read_bytes(data, 4, len as usize).ok()?,
+                Err(_) => &[],
+            };
+            Some(DebugPayload::Repro(Repro { hash: hex(hash) }))
+        }
+        IMAGE_DEBUG_TYPE_EX_DLLCHARACTERISTICS => {
+            let flags = read_u32(data, 0).ok()?;
+            Some(DebugPayload::ExDllCharacteristics(ExDllCharacteristics { flags, names: ex_dll_characteristics_names(flags) }))
+        }
+        IMAGE_DEBUG_TYPE_EMBEDDED_PORTABLE_PDB => {
+            if data.get(..4)? != b"MPDB" {
+                return None;
+            }
+            Some(DebugPayload::EmbeddedPortablePdb(EmbeddedPortablePdb {
+                uncompressed_size: read_u32(data, 4).ok()?,
+                compressed_offset: entry.data_offset(pe).ok()? + 8,
+                compressed_size: data.len().saturating_sub(8),
+            }))
+        }
+        _ => None,
+    }
+}
+
+fn decode_codeview(data: &[u8]) -> Option<CodeView> {
+    let signature = data.get(..4)?;
+    let (guid, timestamp, age, path_start) = match signature {
+        b"RSDS" => {
+            let guid = data.get(4..20)?;
+            (Some(format_guid(guid)), None, read_u32(data, 20).ok()?, 24)
+        }
+        b"NB10" => (None, Some(read_u32(data, 8).ok()?), read_u32(data, 12).ok()?, 16),
+        _ => return None,
+    };
+    let path = c_string(data.get(path_start..)?);
+
+    // Symbol servers key PDBs by file name plus GUID (or timestamp) and age.
+    let name = path.rsplit(['\\', '/']).next().unwrap_or(&path).to_string();
+    let key = match (&guid, timestamp) {
+        (Some(guid), _) => format!("{}{age:X}", guid.replace('-', "")),
+        (None, Some(timestamp)) => format!("{timestamp:08X}{age:X}"),
+        (None, None) => String::new(),
+    };
+
+    Some(CodeView {
+        signature: String::from_utf8_lossy(signature).into_owned(),
+        guid,
+        timestamp,
+        age,
+        symbol_store_path: format!("{name}/{key}/{name}"),
+        path,
+    })
+}
+
+fn decode_pogo(data: &[u8]) -> Option<Pogo> {
+    let signature = pogo_signature(read_u32(data, 0).ok()?);
+    let mut entries = Vec::new();
+    let mut pos = 4;
+    while pos + 8 < data.len() {
+        let rva = read_u32(data, pos).ok()?;
+        let size = read_u32(data, pos + 4).ok()?;
+        let name = c_string(&data[pos + 8..]);
+        // Name is null-terminated and padded to a 4-byte boundary.
+        pos = (pos + 8 + name.len() + 1 + 3) & !3;
+        entries.push(PogoEntry { rva, size, name });
+    }
+    Some(Pogo { signature, entries })
+}
+
+/// The signature is a little-endian dword, so `LTCG` is stored as `GCTL`.
+fn pogo_signature(signature: u32) -> String {
+    match signature {
+        0x4C54_4347 => "LTCG".to_string(),
+        0x5047_5500 => "PGU".to_string(),
+        0x5047_4900 => "PGI".to_string(),
+        0x5047_4F00 => "PGO".to_string(),
+        other => format!("0x{other:08X}"),
+    }
+}
+
+fn ex_dll_characteristics_names(flags: u32) -> Vec<&'static str> {
+    let flag_defs = [
+        (0x01, "CET_COMPAT"),
+        (0x02, "CET_COMPAT_STRICT_MODE"),
+        (0x04, "CET_SET_CONTEXT_IP_VALIDATION_RELAXED_MODE"),
+        (0x08, "CET_DYNAMIC_APIS_ALLOW_IN_PROC"),
+        (0x10, "CET_RESERVED_1"),
+        (0x20, "CET_RESERVED_2"),
+        (0x40, "FORWARD_CFI_COMPAT"),
+        (0x80, "HOTPATCH_COMPATIBLE"),
+    ];
+
+    flag_defs
+        .iter()
+        .filter(|(mask, _)| flags & mask != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+/// Format a little-endian `GUID` the way Windows displays it.
+pub(crate) fn format_guid(bytes: &[u8]) -> String {
+    format!(
+        "{:08X}-{:04X}-{:04X}-{}-{}",
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        u16::from_le_bytes([bytes[4], bytes[5]]),
+        u16::from_le_bytes([bytes[6], bytes[7]]),
+        hex(&bytes[8..10]).to_uppercase(),
+        hex(&bytes[10..16]).to_uppercase(),
+    )
+}
+
+fn c_string(data: &[u8]) -> String {
+    let len = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..len]).into_owned()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,172 @@
+use serde::Serialize;
+
+use crate::errors::{Error, Result};
+use crate::import_table::{read_thunks, ImportEntry, ImportedDll, ImportedFunction};
+use crate::parser::PeFile;
+use crate::utils::{read_dll_names, read_u32, rva_to_offset};
+
+/// Eight u32 fields of `ImgDelayDescr`.
+const ENTRY_SIZE: usize = 8 * 4;
+/// `dlattrRva`: the descriptor holds RVAs rather than VAs.
+const DLATTR_RVA: u32 = 0x1;
+
+/// One `ImgDelayDescr` entry, with addresses exactly as stored.
+///
+/// When `attributes` lacks the RVA bit (pre-VC7 linkers) every address field
+/// is a virtual address instead of an RVA.
+#[derive(Debug, Serialize)]
+pub struct DelayImportEntry {
+    pub attributes: u32,
+    /// Address of the ASCII DLL name.
+    pub dll_name: u32,
+    /// Address of the `HMODULE` slot filled in when the DLL is loaded.
+    pub module_handle: u32,
+    /// Address of the delay-load IAT.
+    pub import_address_table: u32,
+    /// Address of the import name table.
+    pub import_name_table: u32,
+    /// Address of the optional bound IAT.
+    pub bound_import_address_table: u32,
+    /// Address of the optional copy of the IAT used to unload the DLL.
+    pub unload_information_table: u32,
+    /// Timestamp of the bound DLL, or 0 if not bound.
+    pub time_date_stamp: u32,
+}
+
+impl DelayImportEntry {
+    /// Whether the address fields are RVAs (`dlattrRva` set).
+    pub fn is_rva_based(&self) -> bool {
+        self.attributes & DLATTR_RVA != 0
+    }
+
+    /// Convert one of this descriptor's address fields to an RVA.
+    pub fn to_rva(&self, pe: &PeFile, address: u32) -> Option<u32> {
+        match address {
+            0 => None,
+            _ if self.is_rva_based() => Some(address),
+            _ => pe.va_to_rva(address as u64),
+        }
+    }
+
+    /// This descriptor in the shape of a regular import descriptor, with
+    /// addresses converted to RVAs (0 if they don't map) and `delay_loaded` set.
+    pub fn to_import_entry(&self, pe: &PeFile) -> ImportEntry {
+        ImportEntry {
+            original_first_thunk: self.to_rva(pe, self.import_name_table).unwrap_or(0),
+            time_date_stamp: self.time_date_stamp,
+            forwarder_chain: 0,
+            name: self.to_rva(pe, self.dll_name).unwrap_or(0),
+            first_thunk: self.to_rva(pe, self.import_address_table).unwrap_or(0),
+            delay_loaded: true,
+        }
+    }
+}
+
+/// A delay-loaded DLL together with every function resolved from its name table.
+#[derive(Debug, Serialize)]
+pub struct DelayImportedDll {
+    /// Name of the delay-loaded DLL.
+    pub dll_name: String,
+    /// Raw descriptor this DLL was resolved from.
+    pub descriptor: DelayImportEntry,
+    /// RVA of the `HMODULE` slot.
+    pub module_handle_rva: Option<u32>,
+    /// RVA of the delay-load IAT.
+    pub iat_rva: Option<u32>,
+    /// RVA of the bound IAT, if present.
+    pub bound_iat_rva: Option<u32>,
+    /// RVA of the unload IAT, if present.
+    pub unload_iat_rva: Option<u32>,
+    /// Functions imported from the DLL, in thunk order.
+    pub functions: Vec<ImportedFunction>,
+}
+
+impl DelayImportedDll {
+    /// This DLL as a regular import, for dependency listings that cover both
+    /// tables; its descriptor has `delay_loaded` set.
+    pub fn to_imported_dll(&self, pe: &PeFile) -> ImportedDll {
+        ImportedDll {
+            dll_name: self.dll_name.clone(),
+            descriptor: self.descriptor.to_import_entry(pe),
+            functions: self.functions.clone(),
+        }
+    }
+}
+
+/// Parse the delay import directory (data directory 13) and return its descriptors.
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if the directory is absent or its RVA
+/// or size does not map into the file buffer.
+pub fn parse_delay_import_table(pe: &PeFile) -> Result<Vec<DelayImportEntry>> {
+    let delay_dir = pe.optional_header.data_directory()[13];
+    if delay_dir.virtual_address == 0 {
+        return Err(Error::InvalidTableOffset);
+    }
+    let offset = rva_to_offset(pe, delay_dir.virtual_address)
+        .ok_or(Error::InvalidTableOffset)?;
+    let slice = pe.buffer
+        .get(offset..offset + delay_dir.size as usize)
+        .ok_or(Error::InvalidTableOffset)?;
+
+    let mut entries = Vec::new();
+    let mut pos = 0;
+
+    // Iterate descriptors until an all-zero terminator.
+    while (pos + 1) * ENTRY_SIZE <= slice.len() {
+        let block = &slice[pos * ENTRY_SIZE..(pos + 1) * ENTRY_SIZE];
+        if block.iter().all(|&byte| byte == 0) {
+            break;
+        }
+
+        entries.push(DelayImportEntry {
+            attributes:                 read_u32(block, 0)?,
+            dll_name:                   read_u32(block, 4)?,
+            module_handle:              read_u32(block, 8)?,
+            import_address_table:       read_u32(block, 12)?,
+            import_name_table:          read_u32(block, 16)?,
+            bound_import_address_table: read_u32(block, 20)?,
+            unload_information_table:   read_u32(block, 24)?,
+            time_date_stamp:            read_u32(block, 28)?,
+        });
+
+        pos += 1;
+    }
+
+    Ok(entries)
+}
+
+/// Parse the delay import directory and resolve the DLL name and every thunk of each descriptor.
+///
+/// Both the current RVA-based layout and the old VA-based one are handled;
+/// names and ordinals are decoded the same way as for regular imports.
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if the directory or a DLL name does
+/// not map into the file buffer.
+pub fn parse_delay_imports(pe: &PeFile) -> Result<Vec<DelayImportedDll>> {
+    parse_delay_import_table(pe)?
+        .into_iter()
+        .map(|descriptor| {
+            let name_rva = descriptor.to_rva(pe, descriptor.dll_name).ok_or(Error::InvalidTableOffset)?;
+            let dll_name = read_dll_names(pe, name_rva)?;
+            let iat_rva = descriptor.to_rva(pe, descriptor.import_address_table);
+            let name_base = if descriptor.is_rva_based() { 0 } else { pe.optional_header.image_base() };
+
+            let functions = match descriptor.to_rva(pe, descriptor.import_name_table) {
+                Some(int_rva) => read_thunks(pe, int_rva, iat_rva.unwrap_or(0), name_base),
+                None => Vec::new(),
+            };
+
+            Ok(DelayImportedDll {
+                dll_name,
+                module_handle_rva: descriptor.to_rva(pe, descriptor.module_handle),
+                iat_rva,
+                bound_iat_rva: descriptor.to_rva(pe, descriptor.bound_import_address_table),
+                unload_iat_rva: descriptor.to_rva(pe, descriptor.unload_information_table),
+                descriptor,
+                functions,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,203 @@
+//! Minimal DER reader used by the Authenticode decoder.
+
+use crate::errors::{Error, Result};
+
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+pub(crate) const TAG_OCTET_STRING: u8 = 0x04;
+pub(crate) const TAG_OID: u8 = 0x06;
+pub(crate) const TAG_UTF8_STRING: u8 = 0x0C;
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+pub(crate) const TAG_SET: u8 = 0x31;
+pub(crate) const TAG_PRINTABLE_STRING: u8 = 0x13;
+pub(crate) const TAG_T61_STRING: u8 = 0x14;
+pub(crate) const TAG_IA5_STRING: u8 = 0x16;
+pub(crate) const TAG_UTC_TIME: u8 = 0x17;
+pub(crate) const TAG_GENERALIZED_TIME: u8 = 0x18;
+pub(crate) const TAG_UNIVERSAL_STRING: u8 = 0x1C;
+pub(crate) const TAG_BMP_STRING: u8 = 0x1E;
+
+/// Context-specific constructed tag `[n]`.
+pub(crate) const fn context(n: u8) -> u8 {
+    0xA0 | n
+}
+
+/// Context-specific primitive tag `[n] IMPLICIT`.
+pub(crate) const fn context_primitive(n: u8) -> u8 {
+    0x80 | n
+}
+
+/// One tag-length-value element.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Tlv<'a> {
+    pub tag: u8,
+    /// Contents octets.
+    pub value: &'a [u8],
+}
+
+impl<'a> Tlv<'a> {
+    /// Reader over this element's contents.
+    pub fn reader(&self) -> Reader<'a> {
+        Reader::new(self.value)
+    }
+
+    /// Fail with `Error::InvalidData` unless the tag is `tag`.
+    pub fn expect(self, tag: u8) -> Result<Self> {
+        if self.tag == tag { Ok(self) } else { Err(Error::InvalidData) }
+    }
+
+    /// Decode an OBJECT IDENTIFIER into dotted form.
+    pub fn oid(&self) -> Result<String> {
+        if self.tag != TAG_OID || self.value.is_empty() {
+            return Err(Error::InvalidData);
+        }
+        let first = self.value[0];
+        let mut parts = vec![(first / 40).min(2) as u64, (first - 40 * (first / 40).min(2)) as u64];
+        let mut arc = 0u64;
+        for &byte in &self.value[1..] {
+            arc = arc.checked_mul(128).ok_or(Error::InvalidData)? | (byte & 0x7F) as u64;
+            if byte & 0x80 == 0 {
+                parts.push(arc);
+                arc = 0;
+            }
+        }
+        Ok(parts.iter().map(u64::to_string).collect::<Vec<_>>().join("."))
+    }
+
+    /// INTEGER as uppercase hex, leading sign byte stripped (serial numbers).
+    pub fn integer_hex(&self) -> Result<String> {
+        let bytes = match self.value {
+            [0, rest @ ..] if !rest.is_empty() => rest,
+            bytes => bytes,
+        };
+        Ok(bytes.iter().map(|b| format!("{b:02X}")).collect())
+    }
+
+    /// Small non-negative INTEGER.
+    pub fn integer_u64(&self) -> Result<u64> {
+        if self.tag != TAG_INTEGER || self.value.is_empty() || self.value.len() > 9 {
+            return Err(Error::InvalidData);
+        }
+        Ok(self.value.iter().fold(0u64, |acc, &b| acc << 8 | b as u64))
+    }
+
+    /// Any of the ASN.1 string types, decoded to UTF-8.
+    pub fn string(&self) -> Result<String> {
+        match self.tag {
+            TAG_UTF8_STRING | TAG_PRINTABLE_STRING | TAG_IA5_STRING | TAG_T61_STRING => {
+                Ok(String::from_utf8_lossy(self.value).into_owned())
+            }
+            TAG_BMP_STRING => Ok(utf16_be(self.value)),
+            TAG_UNIVERSAL_STRING => Ok(self.value
+                .chunks_exact(4)
+                .filter_map(|c| char::from_u32(u32::from_be_bytes([c[0], c[1], c[2], c[3]])))
+                .collect()),
+            _ => Err(Error::InvalidData),
+        }
+    }
+
+    /// UTCTime or GeneralizedTime formatted as `YYYY-MM-DD HH:MM:SS`.
+    pub fn time(&self) -> Result<String> {
+        let text = std::str::from_utf8(self.value).map_err(|_| Error::InvalidData)?;
+        let digits = text.trim_end_matches('Z');
+        if !digits.is_ascii() {
+            return Err(Error::InvalidData);
+        }
+        let full = match self.tag {
+            // Two-digit years: 50-99 are 19xx, 00-49 are 20xx.
+            TAG_UTC_TIME if digits.len() >= 12 => {
+                let century = if digits[..2].parse::<u8>().map_err(|_| Error::InvalidData)? >= 50 { "19" } else { "20" };
+                format!("{century}{digits}")
+            }
+            TAG_GENERALIZED_TIME if digits.len() >= 14 => digits.to_string(),
+            _ => return Err(Error::InvalidData),
+        };
+        if !full[..14].bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::InvalidData);
+        }
+        Ok(format!("{}-{}-{} {}:{}:{}", &full[0..4], &full[4..6], &full[6..8], &full[8..10], &full[10..12], &full[12..14]))
+    }
+}
+
+/// Sequential reader over the contents of a constructed element.
+#[derive(Debug, Clone)]
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    /// Tag of the next element, without consuming it.
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    /// Read the next element.
+    pub fn read(&mut self) -> Result<Tlv<'a>> {
+        let start = self.pos;
+        let tag = *self.data.get(start).ok_or(Error::InvalidData)?;
+        // High tag numbers never occur in the structures we decode.
+        if tag & 0x1F == 0x1F {
+            return Err(Error::InvalidData);
+        }
+        let first = *self.data.get(start + 1).ok_or(Error::InvalidData)?;
+        let (len, header) = if first < 0x80 {
+            (first as usize, 2)
+        } else {
+            let count = (first & 0x7F) as usize;
+            // 0x80 is BER's indefinite length, which DER forbids.
+            if count == 0 || count > 4 {
+                return Err(Error::InvalidData);
+            }
+            let bytes = self.data.get(start + 2..start + 2 + count).ok_or(Error::InvalidData)?;
+            (bytes.iter().fold(0usize, |acc, &b| acc << 8 | b as usize), 2 + count)
+        };
+        let end = start
+            .checked_add(header)
+            .and_then(|v| v.checked_add(len))
+            .filter(|&end| end <= self.data.len())
+            .ok_or(Error::InvalidData)?;
+
+        self.pos = end;
+        Ok(Tlv { tag, value: &self.data[start + header..end] })
+    }
+
+    /// Read the next element and check its tag.
+    pub fn expect(&mut self, tag: u8) -> Result<Tlv<'a>> {
+        self.read()?.expect(tag)
+    }
+
+    /// Read the next element only if it carries `tag`.
+    pub fn optional(&mut self, tag: u8) -> Result<Option<Tlv<'a>>> {
+        if self.peek_tag() == Some(tag) { self.read().map(Some) } else { Ok(None) }
+    }
+
+    /// Read every remaining element.
+    pub fn all(mut self) -> Result<Vec<Tlv<'a>>> {
+        let mut items = Vec::new();
+        while !self.is_empty() {
+            items.push(self.read()?);
+        }
+        Ok(items)
+    }
+}
+
+/// Parse a buffer that must start with a single element (trailing padding is ignored).
+pub(crate) fn parse(data: &[u8]) -> Result<Tlv<'_>> {
+    Reader::new(data).read()
+}
+
+fn utf16_be(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
@@ -1,23 +1,54 @@
-use crate::headers::DosHeader;
-use crate::errors::Result;
-use std::ptr;
+use crate::headers::{DosHeader, DOS_MAGIC};
+use crate::errors::{Error, Result};
+use crate::utils::{read_bytes, ByteReader};
 
 impl DosHeader {
+    /// Size of `IMAGE_DOS_HEADER` on disk.
+    pub const SIZE: usize = 0x40;
+
     /// Read the DOS header (`IMAGE_DOS_HEADER`) from the start of `buffer`.
     ///
-    /// # Safety
+    /// Every field is decoded through a bounds-checked reader, so a truncated
+    /// buffer is reported instead of being read past its end.
     ///
-    /// This does an unaligned, direct memory read of the `#[repr(C)]` struct.
-    /// The caller must guarantee that:
-    /// - `buffer.len() >= std::mem::size_of::<DosHeader>()` (at least 64 bytes),  
-    /// - `buffer` actually begins with a valid DOS header.
+    /// # Errors
     ///
-    /// In practice this is automatically satisfied by using
-    /// [`PeFile::parse()`], which checks the magic and ensures `buffer`
-    /// contains the full DOS header before calling this.
+    /// - [`Error::InvalidSize`] if `buffer` is shorter than 64 bytes.
+    /// - [`Error::InvalidMagic`] if it doesn't start with `MZ`.
     pub fn parse(buffer: &[u8]) -> Result<Self> {
-        // SAFETY: see the `# Safety` section above
-        let header = unsafe { ptr::read_unaligned(buffer.as_ptr() as *const DosHeader) };
+        let header = Self::read(buffer)?;
+        if header.e_magic != DOS_MAGIC {
+            return Err(Error::InvalidMagic(header.e_magic));
+        }
+        Ok(header)
+    }
+
+    /// Decode the DOS header fields without checking the `MZ` magic.
+    pub(crate) fn read(buffer: &[u8]) -> Result<Self> {
+        read_bytes(buffer, 0, Self::SIZE)?;
+
+        let mut r = ByteReader::new(buffer, 0);
+        let header = DosHeader {
+            e_magic:    r.u16()?,
+            e_cblp:     r.u16()?,
+            e_cp:       r.u16()?,
+            e_crlc:     r.u16()?,
+            e_cparhdr:  r.u16()?,
+            e_minalloc: r.u16()?,
+            e_maxalloc: r.u16()?,
+            e_ss:       r.u16()?,
+            e_sp:       r.u16()?,
+            e_csum:     r.u16()?,
+            e_ip:       r.u16()?,
+            e_cs:       r.u16()?,
+            e_lfarlc:   r.u16()?,
+            e_ovno:     r.u16()?,
+            e_res:      r.u16_array()?,
+            e_oemid:    r.u16()?,
+            e_oeminfo:  r.u16()?,
+            e_res2:     r.u16_array()?,
+            e_lfanew:   r.u32()?,
+        };
         Ok(header)
     }
 
@@ -0,0 +1,272 @@
+use serde::Serialize;
+
+use crate::errors::{Error, Result};
+use crate::headers::DataDirectory;
+use crate::metadata_tables::{parse_metadata_tables, MetadataTables};
+use crate::parser::PeFile;
+use crate::utils::{read_bytes, read_rva_bytes, read_u16, read_u32, rva_to_offset, ByteReader};
+
+/// `BSJB`, the signature at the start of the metadata root.
+const METADATA_SIGNATURE: u32 = 0x424A_5342;
+const COMIMAGE_FLAGS_NATIVE_ENTRYPOINT: u32 = 0x0000_0010;
+/// Stream names are at most 32 bytes, terminator included.
+const MAX_STREAM_NAME: usize = 32;
+
+/// Decoded `IMAGE_COR20_HEADER`, the CLR runtime header.
+#[derive(Debug, Clone, Serialize)]
+pub struct CorHeader {
+    /// `cb`: size of the header in bytes.
+    pub size: u32,
+    pub major_runtime_version: u16,
+    pub minor_runtime_version: u16,
+    pub metadata: DataDirectory,
+    pub flags: u32,
+    /// Names of the `COMIMAGE_FLAGS_*` bits set in `flags`.
+    pub flag_names: Vec<&'static str>,
+    /// Metadata token of the managed entry point (`MethodDef` or `File`).
+    pub entry_point_token: Option<u32>,
+    /// RVA of a native entry point, when `NATIVE_ENTRYPOINT` is set.
+    pub entry_point_rva: Option<u32>,
+    pub resources: DataDirectory,
+    pub strong_name_signature: DataDirectory,
+    pub code_manager_table: DataDirectory,
+    pub vtable_fixups: DataDirectory,
+    pub export_address_table_jumps: DataDirectory,
+    pub managed_native_header: DataDirectory,
+}
+
+/// One `IMAGE_COR_VTABLEFIXUP` entry, used by mixed-mode images to expose
+/// managed methods to native callers.
+#[derive(Debug, Clone, Serialize)]
+pub struct VTableFixup {
+    /// RVA of the slot array.
+    pub rva: u32,
+    /// Number of slots.
+    pub count: u16,
+    pub fixup_type: u16,
+    /// Names of the `COR_VTABLE_*` bits set in `fixup_type`.
+    pub type_names: Vec<&'static str>,
+    /// Method tokens stored in the slots, before the loader patches them.
+    pub tokens: Vec<u32>,
+}
+
+/// A stream header from the metadata root.
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamHeader {
+    /// Stream name, e.g. `#~`, `#Strings`, `#US`, `#GUID` or `#Blob`.
+    pub name: String,
+    /// Offset from the start of the metadata root.
+    pub offset: u32,
+    pub size: u32,
+    /// RVA of the stream data.
+    pub rva: u32,
+}
+
+impl StreamHeader {
+    /// Borrow the stream data.
+    pub fn data<'a>(&self, pe: &'a PeFile) -> Result<&'a [u8]> {
+        read_rva_bytes(pe, self.rva, self.size as usize)
+    }
+}
+
+/// The metadata root (`BSJB` header) and its stream directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetadataRoot {
+    pub rva: u32,
+    pub major_version: u16,
+    pub minor_version: u16,
+    /// Runtime version the assembly was built against, e.g. `v4.0.30319`.
+    pub version: String,
+    pub flags: u16,
+    pub streams: Vec<StreamHeader>,
+}
+
+impl MetadataRoot {
+    /// Find a stream by name.
+    pub fn stream(&self, name: &str) -> Option<&StreamHeader> {
+        self.streams.iter().find(|stream| stream.name == name)
+    }
+}
+
+/// CLR data of a managed or mixed-mode image.
+#[derive(Debug, Clone, Serialize)]
+pub struct DotNet {
+    pub header: CorHeader,
+    pub metadata: MetadataRoot,
+    pub vtable_fixups: Vec<VTableFixup>,
+    /// Decoded `#~` stream; `None` if it is missing or malformed.
+    pub tables: Option<MetadataTables>,
+}
+
+/// Parse the CLR runtime header (data directory 14, "COM descriptor").
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if the directory is absent or does not
+/// map into the file, or `Error::InvalidSize` if the header is truncated.
+pub fn parse_cor_header(pe: &PeFile) -> Result<CorHeader> {
+    let clr_dir = pe.optional_header.data_directory()[14];
+    if clr_dir.virtual_address == 0 {
+        return Err(Error::InvalidTableOffset);
+    }
+    let offset = rva_to_offset(pe, clr_dir.virtual_address).ok_or(Error::InvalidTableOffset)?;
+
+    let mut r = ByteReader::new(&pe.buffer, offset);
+    let size                  = r.u32()?;
+    let major_runtime_version = r.u16()?;
+    let minor_runtime_version = r.u16()?;
+    let metadata              = directory(&mut r)?;
+    let flags                 = r.u32()?;
+    let entry_point           = r.u32()?;
+
+    Ok(CorHeader {
+        size,
+        major_runtime_version,
+        minor_runtime_version,
+        metadata,
+        flags,
+        flag_names: cor_flag_names(flags),
+        entry_point_token: (flags & COMIMAGE_FLAGS_NATIVE_ENTRYPOINT == 0 && entry_point != 0).then_some(entry_point),
+        entry_point_rva: (flags & COMIMAGE_FLAGS_NATIVE_ENTRYPOINT != 0).then_some(entry_point),
+        resources: directory(&mut r)?,
+        strong_name_signature: directory(&mut r)?,
+        code_manager_table: directory(&mut r)?,
+        vtable_fixups: directory(&mut r)?,
+        export_address_table_jumps: directory(&mut r)?,
+        managed_native_header: directory(&mut r)?,
+    })
+}
+
+/// Parse the CLR header together with the metadata root, its tables and
+/// the VTable fixups.
+///
+/// # Errors
+/// Returns the errors of [`parse_cor_header`], `Error::InvalidTableOffset` if
+/// the metadata does not map into the file, or `Error::InvalidData` if it
+/// lacks the `BSJB` signature or a stream header is malformed.
+pub fn parse_dotnet(pe: &PeFile) -> Result<DotNet> {
+    let header = parse_cor_header(pe)?;
+    let metadata = parse_metadata_root(pe, header.metadata)?;
+    let vtable_fixups = parse_vtable_fixups(pe, header.vtable_fixups)?;
+    let tables = parse_metadata_tables(pe, &metadata).ok();
+    Ok(DotNet { header, metadata, vtable_fixups, tables })
+}
+
+fn parse_metadata_root(pe: &PeFile, dir: DataDirectory) -> Result<MetadataRoot> {
+    if dir.virtual_address == 0 {
+        return Err(Error::InvalidTableOffset);
+    }
+    let root = read_rva_bytes(pe, dir.virtual_address, dir.size as usize)?;
+    if read_u32(root, 0)? != METADATA_SIGNATURE {
+        return Err(Error::InvalidData);
+    }
+    let major_version = read_u16(root, 4)?;
+    let minor_version = read_u16(root, 6)?;
+    // The version length includes padding to a 4-byte boundary.
+    let version_len = read_u32(root, 12)? as usize;
+    let version_bytes = read_bytes(root, 16, version_len)?;
+    let end = version_bytes.iter().position(|&b| b == 0).unwrap_or(version_bytes.len());
+    let version = String::from_utf8_lossy(&version_bytes[..end]).into_owned();
+
+    let mut pos = 16 + version_len;
+    let flags = read_u16(root, pos)?;
+    let count = read_u16(root, pos + 2)?;
+    pos += 4;
+
+    let mut streams = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let offset = read_u32(root, pos)?;
+        let size = read_u32(root, pos + 4)?;
+        let name_area = root.get(pos + 8..).ok_or(Error::InvalidData)?;
+        let name_len = name_area
+            .iter()
+            .take(MAX_STREAM_NAME)
+            .position(|&b| b == 0)
+            .ok_or(Error::InvalidData)?;
+        let name = String::from_utf8_lossy(&name_area[..name_len]).into_owned();
+        pos += 8 + (name_len + 1).div_ceil(4) * 4;
+
+        streams.push(StreamHeader {
+            name,
+            offset,
+            size,
+            rva: dir.virtual_address.checked_add(offset).ok_or(Error::InvalidData)?,
+        });
+    }
+
+    Ok(MetadataRoot {
+        rva: dir.virtual_address,
+        major_version,
+        minor_version,
+        version,
+        flags,
+        streams,
+    })
+}
+
+fn parse_vtable_fixups(pe: &PeFile, dir: DataDirectory) -> Result<Vec<VTableFixup>> {
+    if dir.virtual_address == 0 {
+        return Ok(Vec::new());
+    }
+    let table = read_rva_bytes(pe, dir.virtual_address, dir.size as usize)?;
+    table
+        .chunks_exact(8)
+        .map(|entry| {
+            let rva = read_u32(entry, 0)?;
+            let count = read_u16(entry, 4)?;
+            let fixup_type = read_u16(entry, 6)?;
+            // Slots are 64-bit when COR_VTABLE_64BIT is set, 32-bit otherwise;
+            // either way the low 32 bits hold the token.
+            let width = if fixup_type & 0x02 != 0 { 8 } else { 4 };
+            let slots = read_rva_bytes(pe, rva, count as usize * width)?;
+            let tokens = slots
+                .chunks_exact(width)
+                .map(|slot| read_u32(slot, 0))
+                .collect::<Result<_>>()?;
+            Ok(VTableFixup {
+                rva,
+                count,
+                fixup_type,
+                type_names: vtable_type_names(fixup_type),
+                tokens,
+            })
+        })
+        .collect()
+}
+
+fn directory(r: &mut ByteReader) -> Result<DataDirectory> {
+    Ok(DataDirectory { virtual_address: r.u32()?, size: r.u32()? })
+}
+
+fn cor_flag_names(flags: u32) -> Vec<&'static str> {
+    let flag_defs = [
+        (0x0000_0001, "ILONLY"),
+        (0x0000_0002, "32BITREQUIRED"),
+        (0x0000_0004, "IL_LIBRARY"),
+        (0x0000_0008, "STRONGNAMESIGNED"),
+        (0x0000_0010, "NATIVE_ENTRYPOINT"),
+        (0x0001_0000, "TRACKDEBUGDATA"),
+        (0x0002_0000, "32BITPREFERRED"),
+    ];
+
+    flag_defs
+        .iter()
+        .filter(|(mask, _)| flags & mask != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+fn vtable_type_names(fixup_type: u16) -> Vec<&'static str> {
+    let flag_defs = [
+        (0x01, "32BIT"),
+        (0x02, "64BIT"),
+        (0x04, "FROM_UNMANAGED"),
+        (0x08, "FROM_UNMANAGED_RETAIN_APPDOMAIN"),
+        (0x10, "CALL_MOST_DERIVED"),
+    ];
+
+    flag_defs
+        .iter()
+        .filter(|(mask, _)| fixup_type & mask != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
@@ -0,0 +1,110 @@
+use serde::Serialize;
+
+use crate::headers::SectionHeader;
+use crate::parser::PeFile;
+
+/// Shannon entropy of a window of the file.
+#[derive(Debug, Clone, Serialize)]
+pub struct EntropyWindow {
+    /// File offset of the window.
+    pub offset: usize,
+    /// Number of bytes in the window (shorter for the last one).
+    pub size: usize,
+    pub entropy: f64,
+}
+
+/// Entropy of the regions of the file, in bits per byte.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileEntropy {
+    pub file: f64,
+    /// Headers up to `size_of_headers`.
+    pub headers: f64,
+    /// See [`PeFile::overlay_range`]; `None` if there is no overlay.
+    pub overlay: Option<f64>,
+}
+
+/// Shannon entropy of `data` in bits per byte, from 0.0 (constant) to 8.0
+/// (uniformly random). Packed or encrypted data usually scores above 7.
+pub fn shannon_entropy(data: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    for &byte in data {
+        counts[byte as usize] += 1;
+    }
+    entropy_of_counts(&counts, data.len())
+}
+
+fn entropy_of_counts(counts: &[usize; 256], total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    let total = total as f64;
+    counts
+        .iter()
+        .filter(|&&count| count != 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+impl PeFile<'_> {
+    /// Entropy of the whole file, the headers and the overlay.
+    pub fn entropy(&self) -> FileEntropy {
+        let headers_end = (self.optional_header.size_of_headers() as usize).min(self.buffer.len());
+
+        FileEntropy {
+            file: shannon_entropy(&self.buffer),
+            headers: shannon_entropy(&self.buffer[..headers_end]),
+            overlay: self.overlay_data().map(shannon_entropy),
+        }
+    }
+
+    /// Entropy of a section's raw data; 0.0 for sections without file data.
+    pub fn section_entropy(&self, section: &SectionHeader) -> f64 {
+        shannon_entropy(self.section_data(section))
+    }
+
+    /// Sliding-window entropy profile over the file.
+    ///
+    /// Windows of `window` bytes start every `step` bytes until one reaches
+    /// the end of the file; only that last one can be shorter. Returns nothing
+    /// if either size is zero.
+    pub fn entropy_profile(&self, window: usize, step: usize) -> Vec<EntropyWindow> {
+        if window == 0 || step == 0 {
+            return Vec::new();
+        }
+        let data = &self.buffer[..];
+        let mut counts = [0usize; 256];
+        let (mut start, mut end) = (0, 0);
+        let mut profile = Vec::new();
+        while start < data.len() {
+            // Slide the histogram instead of recounting overlapping windows.
+            let new_end = start.saturating_add(window).min(data.len());
+            if start >= end {
+                counts = [0; 256];
+                end = start;
+            }
+            for &byte in &data[end..new_end] {
+                counts[byte as usize] += 1;
+            }
+            end = new_end;
+
+            profile.push(EntropyWindow {
+                offset: start,
+                size: end - start,
+                entropy: entropy_of_counts(&counts, end - start),
+            });
+            if end == data.len() {
+                break;
+            }
+
+            let next = start.saturating_add(step);
+            for &byte in &data[start..next.min(end)] {
+                counts[byte as usize] -= 1;
+            }
+            start = next;
+        }
+        profile
+    }
+}
@@ -26,6 +26,12 @@ pub enum Error {
 
     /// Buffer was smaller than we expected.
     InvalidSize { expected: usize, found: usize },
+
+    /// Base relocation type we don't know how to apply.
+    UnsupportedRelocation(u8),
+
+    /// Machine type whose structures we don't decode.
+    UnsupportedMachine(u16),
 }
 
 impl fmt::Display for Error {
@@ -47,6 +53,10 @@ impl fmt::Display for Error {
                 write!(f, "Malformed data in buffer"),
             Error::InvalidSize { expected, found } =>
                 write!(f, "Expected at least {expected} bytes, found {found}"),
+            Error::UnsupportedRelocation(kind) =>
+                write!(f, "Unsupported base relocation type {kind}"),
+            Error::UnsupportedMachine(machine) =>
+                write!(f, "Unsupported machine type: 0x{machine:04X}"),
         }
     }
 }
@@ -0,0 +1,450 @@
+use serde::Serialize;
+
+use crate::errors::{Error, Result};
+use crate::parser::PeFile;
+use crate::utils::{read_bytes, read_u16, read_u32, read_u8, rva_to_offset};
+
+const MACHINE_AMD64: u16 = 0x8664;
+const MACHINE_ARM64: u16 = 0xAA64;
+
+const UNW_FLAG_EHANDLER: u8 = 0x1;
+const UNW_FLAG_UHANDLER: u8 = 0x2;
+const UNW_FLAG_CHAININFO: u8 = 0x4;
+
+/// How many chained `UNWIND_INFO` records we follow before giving up.
+const MAX_CHAIN_DEPTH: usize = 32;
+
+const GPR_NAMES: [&str; 16] = [
+    "RAX", "RCX", "RDX", "RBX", "RSP", "RBP", "RSI", "RDI",
+    "R8", "R9", "R10", "R11", "R12", "R13", "R14", "R15",
+];
+const XMM_NAMES: [&str; 16] = [
+    "XMM0", "XMM1", "XMM2", "XMM3", "XMM4", "XMM5", "XMM6", "XMM7",
+    "XMM8", "XMM9", "XMM10", "XMM11", "XMM12", "XMM13", "XMM14", "XMM15",
+];
+
+/// One decoded x64 `UNWIND_CODE` operation (which may span several slots).
+#[derive(Debug, Clone, Serialize)]
+pub struct UnwindCode {
+    /// Offset from the start of the prolog of the end of the instruction.
+    pub code_offset: u8,
+    /// Raw `UWOP_*` value.
+    pub op: u8,
+    /// Readable form of `op`.
+    pub op_name: &'static str,
+    pub op_info: u8,
+    /// Register pushed or saved, for the operations that name one.
+    pub register: Option<&'static str>,
+    /// Allocation size or stack offset, already scaled.
+    pub value: Option<u32>,
+}
+
+/// Decoded x64 `UNWIND_INFO`.
+#[derive(Debug, Clone, Serialize)]
+pub struct UnwindInfo {
+    pub version: u8,
+    pub flags: u8,
+    /// Names of the `UNW_FLAG_*` bits set in `flags`.
+    pub flag_names: Vec<&'static str>,
+    pub size_of_prolog: u8,
+    pub count_of_codes: u8,
+    /// Frame pointer register, if the function establishes one.
+    pub frame_register: Option<&'static str>,
+    /// Offset of the frame pointer from RSP, already scaled by 16.
+    pub frame_offset: u32,
+    pub codes: Vec<UnwindCode>,
+    /// Language-specific handler (`EHANDLER`/`UHANDLER`).
+    pub handler_rva: Option<u32>,
+    /// Start of the handler data that follows the handler RVA.
+    pub handler_data_rva: Option<u32>,
+    /// Primary entry this one continues (`CHAININFO`).
+    pub chained: Option<Box<RuntimeFunction>>,
+}
+
+/// x64 `RUNTIME_FUNCTION` entry.
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeFunction {
+    pub begin_address: u32,
+    pub end_address: u32,
+    pub unwind_info_address: u32,
+    /// Decoded unwind info, if it maps into the file.
+    pub unwind_info: Option<UnwindInfo>,
+}
+
+/// ARM64 packed unwind data stored directly in the `.pdata` entry.
+#[derive(Debug, Clone, Serialize)]
+pub struct Arm64PackedUnwind {
+    /// 1 for a normal function, 2 for a fragment without a prolog.
+    pub flag: u8,
+    pub reg_f: u8,
+    pub reg_i: u8,
+    /// Whether the integer parameter registers are homed.
+    pub homes_parameters: bool,
+    pub cr: u8,
+    /// Total stack allocation in bytes.
+    pub frame_size: u32,
+}
+
+/// One epilog scope of an ARM64 `.xdata` record.
+#[derive(Debug, Clone, Serialize)]
+pub struct Arm64EpilogScope {
+    /// Offset of the epilog from the function start, in bytes.
+    pub start_offset: u32,
+    /// Index into the unwind code bytes where this epilog's codes start.
+    pub start_index: u16,
+}
+
+/// One ARM64 unwind code.
+#[derive(Debug, Clone, Serialize)]
+pub struct Arm64UnwindCode {
+    /// Index of the code's first byte in the unwind code array.
+    pub index: usize,
+    pub name: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+/// ARM64 unpacked unwind data (`.xdata` record).
+#[derive(Debug, Clone, Serialize)]
+pub struct Arm64XData {
+    pub version: u8,
+    /// Single epilog described by `epilog_count` as a code index (`E` bit).
+    pub single_epilog: bool,
+    pub epilog_count: u16,
+    pub code_words: u8,
+    pub epilog_scopes: Vec<Arm64EpilogScope>,
+    pub codes: Vec<Arm64UnwindCode>,
+    /// Exception handler (`X` bit).
+    pub handler_rva: Option<u32>,
+    pub handler_data_rva: Option<u32>,
+}
+
+/// Unwind data referenced by an ARM64 `.pdata` entry.
+#[derive(Debug, Clone, Serialize)]
+pub enum Arm64Unwind {
+    Packed(Arm64PackedUnwind),
+    Unpacked(Arm64XData),
+}
+
+/// ARM64 `.pdata` entry.
+#[derive(Debug, Clone, Serialize)]
+pub struct Arm64RuntimeFunction {
+    pub begin_address: u32,
+    /// Raw second word: packed data, or the `.xdata` RVA when the low bits are 0.
+    pub unwind_data: u32,
+    /// `begin_address` plus the function length, when it could be decoded.
+    pub end_address: Option<u32>,
+    pub unwind: Option<Arm64Unwind>,
+}
+
+/// Decoded exception directory, by architecture.
+#[derive(Debug, Clone, Serialize)]
+pub enum ExceptionTable {
+    X64(Vec<RuntimeFunction>),
+    Arm64(Vec<Arm64RuntimeFunction>),
+}
+
+impl ExceptionTable {
+    /// `(start, end)` RVA ranges of every entry whose extent is known.
+    ///
+    /// Chained x64 entries and ARM64 fragments describe parts of a larger
+    /// function and are listed as separate ranges.
+    pub fn function_ranges(&self) -> Vec<(u32, u32)> {
+        match self {
+            ExceptionTable::X64(functions) => functions
+                .iter()
+                .map(|function| (function.begin_address, function.end_address))
+                .collect(),
+            ExceptionTable::Arm64(functions) => functions
+                .iter()
+                .filter_map(|function| Some((function.begin_address, function.end_address?)))
+                .collect(),
+        }
+    }
+}
+
+/// Parse the exception directory (data directory 3).
+///
+/// Supports x64 `RUNTIME_FUNCTION` entries with their `UNWIND_INFO`, and
+/// ARM64 entries in both packed and `.xdata` forms. Unwind data that doesn't
+/// map into the file is left as `None`. All-zero entries, which incremental
+/// linking leaves as padding, are skipped.
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if the directory is absent or does not
+/// map into the file, `Error::InvalidSize` if the entries are truncated, or
+/// `Error::UnsupportedMachine` for other architectures.
+pub fn parse_exception_table(pe: &PeFile) -> Result<ExceptionTable> {
+    let directory = pe.optional_header.data_directory()[3];
+    if directory.virtual_address == 0 {
+        return Err(Error::InvalidTableOffset);
+    }
+    let offset = rva_to_offset(pe, directory.virtual_address)
+        .ok_or(Error::InvalidTableOffset)?;
+
+    match pe.file_header.machine {
+        MACHINE_AMD64 => {
+            let count = directory.size as usize / 12;
+            read_bytes(&pe.buffer, offset, count * 12)?;
+            let mut functions = Vec::with_capacity(count);
+            for i in 0..count {
+                if read_bytes(&pe.buffer, offset + i * 12, 12)?.iter().all(|&b| b == 0) {
+                    continue;
+                }
+                functions.push(read_runtime_function(pe, offset + i * 12, 0)?);
+            }
+            Ok(ExceptionTable::X64(functions))
+        }
+        MACHINE_ARM64 => {
+            let count = directory.size as usize / 8;
+            read_bytes(&pe.buffer, offset, count * 8)?;
+            let mut functions = Vec::with_capacity(count);
+            for i in 0..count {
+                let begin_address = read_u32(&pe.buffer, offset + i * 8)?;
+                let unwind_data   = read_u32(&pe.buffer, offset + i * 8 + 4)?;
+                if begin_address == 0 && unwind_data == 0 {
+                    continue;
+                }
+                let unwind = decode_arm64_unwind(pe, unwind_data);
+                let length = match &unwind {
+                    Some(Arm64Unwind::Packed(_)) => Some(((unwind_data >> 2) & 0x7FF) * 4),
+                    Some(Arm64Unwind::Unpacked(_)) => {
+                        rva_to_offset(pe, unwind_data)
+                            .and_then(|at| read_u32(&pe.buffer, at).ok())
+                            .map(|header| (header & 0x3FFFF) * 4)
+                    }
+                    None => None,
+                };
+                functions.push(Arm64RuntimeFunction {
+                    begin_address,
+                    unwind_data,
+                    end_address: length.map(|length| begin_address.wrapping_add(length)),
+                    unwind,
+                });
+            }
+            Ok(ExceptionTable::Arm64(functions))
+        }
+        other => Err(Error::UnsupportedMachine(other)),
+    }
+}
+
+fn read_runtime_function(pe: &PeFile, offset: usize, depth: usize) -> Result<RuntimeFunction> {
+    let unwind_info_address = read_u32(&pe.buffer, offset + 8)?;
+    Ok(RuntimeFunction {
+        begin_address: read_u32(&pe.buffer, offset)?,
+        end_address: read_u32(&pe.buffer, offset + 4)?,
+        unwind_info_address,
+        unwind_info: parse_unwind_info(pe, unwind_info_address, depth).ok(),
+    })
+}
+
+fn parse_unwind_info(pe: &PeFile, rva: u32, depth: usize) -> Result<UnwindInfo> {
+    let offset = rva_to_offset(pe, rva).ok_or(Error::InvalidTableOffset)?;
+    let header         = read_u8(&pe.buffer, offset)?;
+    let size_of_prolog = read_u8(&pe.buffer, offset + 1)?;
+    let count_of_codes = read_u8(&pe.buffer, offset + 2)?;
+    let frame          = read_u8(&pe.buffer, offset + 3)?;
+    let version = header & 0x7;
+    let flags = header >> 3;
+
+    let slots = (0..count_of_codes as usize)
+        .map(|i| read_u16(&pe.buffer, offset + 4 + i * 2))
+        .collect::<Result<Vec<_>>>()?;
+
+    // The code array is padded to an even number of slots.
+    let tail = 4 + (count_of_codes as usize).div_ceil(2) * 4;
+    let mut info = UnwindInfo {
+        version,
+        flags,
+        flag_names: unwind_flag_names(flags),
+        size_of_prolog,
+        count_of_codes,
+        frame_register: match frame & 0xF {
+            0 => None,
+            register => Some(GPR_NAMES[register as usize]),
+        },
+        frame_offset: (frame >> 4) as u32 * 16,
+        codes: decode_unwind_codes(&slots, version),
+        handler_rva: None,
+        handler_data_rva: None,
+        chained: None,
+    };
+
+    if flags & UNW_FLAG_CHAININFO != 0 {
+        if depth < MAX_CHAIN_DEPTH {
+            info.chained = Some(Box::new(read_runtime_function(pe, offset + tail, depth + 1)?));
+        }
+    } else if flags & (UNW_FLAG_EHANDLER | UNW_FLAG_UHANDLER) != 0 {
+        info.handler_rva = Some(read_u32(&pe.buffer, offset + tail)?);
+        info.handler_data_rva = Some(rva.wrapping_add(tail as u32 + 4));
+    }
+
+    Ok(info)
+}
+
+fn unwind_flag_names(flags: u8) -> Vec<&'static str> {
+    let flag_defs = [
+        (UNW_FLAG_EHANDLER, "EHANDLER"),
+        (UNW_FLAG_UHANDLER, "UHANDLER"),
+        (UNW_FLAG_CHAININFO, "CHAININFO"),
+    ];
+
+    flag_defs
+        .iter()
+        .filter(|(mask, _)| flags & mask != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+/// Decode the slot array into operations; stops at an unknown or truncated code.
+fn decode_unwind_codes(slots: &[u16], version: u8) -> Vec<UnwindCode> {
+    let mut codes = Vec::new();
+    let mut i = 0;
+    while i < slots.len() {
+        let slot = slots[i];
+        let code_offset = slot as u8;
+        let op = (slot >> 8) as u8 & 0xF;
+        let op_info = (slot >> 12) as u8;
+        let gpr = Some(GPR_NAMES[op_info as usize]);
+        let xmm = Some(XMM_NAMES[op_info as usize]);
+        let next = |n: usize| slots.get(i + n).map(|&slot| slot as u32);
+        let far = || Some(next(1)? | next(2)? << 16);
+
+        let (op_name, used, register, value) = match op {
+            0 => ("PUSH_NONVOL", 1, gpr, None),
+            1 if op_info == 0 => ("ALLOC_LARGE", 2, None, next(1).map(|size| size * 8)),
+            1 => ("ALLOC_LARGE", 3, None, far()),
+            2 => ("ALLOC_SMALL", 1, None, Some(op_info as u32 * 8 + 8)),
+            3 => ("SET_FPREG", 1, None, None),
+            4 => ("SAVE_NONVOL", 2, gpr, next(1).map(|offset| offset * 8)),
+            5 => ("SAVE_NONVOL_FAR", 3, gpr, far()),
+            6 if version < 2 => ("SAVE_XMM", 2, xmm, next(1).map(|offset| offset * 16)),
+            6 => ("EPILOG", 2, None, None),
+            7 if version < 2 => ("SAVE_XMM_FAR", 3, xmm, far()),
+            7 => ("SPARE_CODE", 3, None, None),
+            8 => ("SAVE_XMM128", 2, xmm, next(1).map(|offset| offset * 16)),
+            9 => ("SAVE_XMM128_FAR", 3, xmm, far()),
+            10 => ("PUSH_MACHFRAME", 1, None, None),
+            _ => break,
+        };
+        if i + used > slots.len() {
+            break;
+        }
+
+        codes.push(UnwindCode { code_offset, op, op_name, op_info, register, value });
+        i += used;
+    }
+    codes
+}
+
+fn decode_arm64_unwind(pe: &PeFile, unwind_data: u32) -> Option<Arm64Unwind> {
+    match unwind_data & 0x3 {
+        0 => parse_xdata(pe, unwind_data).ok().map(Arm64Unwind::Unpacked),
+        flag @ (1 | 2) => Some(Arm64Unwind::Packed(Arm64PackedUnwind {
+            flag: flag as u8,
+            reg_f: ((unwind_data >> 13) & 0x7) as u8,
+            reg_i: ((unwind_data >> 16) & 0xF) as u8,
+            homes_parameters: (unwind_data >> 20) & 1 != 0,
+            cr: ((unwind_data >> 21) & 0x3) as u8,
+            frame_size: (unwind_data >> 23) * 16,
+        })),
+        _ => None,
+    }
+}
+
+fn parse_xdata(pe: &PeFile, rva: u32) -> Result<Arm64XData> {
+    let offset = rva_to_offset(pe, rva).ok_or(Error::InvalidTableOffset)?;
+    let header = read_u32(&pe.buffer, offset)?;
+    let has_handler = (header >> 20) & 1 != 0;
+    let single_epilog = (header >> 21) & 1 != 0;
+    let mut epilog_count = ((header >> 22) & 0x1F) as u16;
+    let mut code_words = (header >> 27) as u8;
+    let mut pos = offset + 4;
+
+    // Both counts zero means they spill into an extension word.
+    if epilog_count == 0 && code_words == 0 {
+        let extended = read_u32(&pe.buffer, pos)?;
+        epilog_count = extended as u16;
+        code_words = (extended >> 16) as u8;
+        pos += 4;
+    }
+
+    let mut epilog_scopes = Vec::new();
+    if !single_epilog {
+        for _ in 0..epilog_count {
+            let scope = read_u32(&pe.buffer, pos)?;
+            epilog_scopes.push(Arm64EpilogScope {
+                start_offset: (scope & 0x3FFFF) * 4,
+                start_index: (scope >> 22) as u16,
+            });
+            pos += 4;
+        }
+    }
+
+    let code_bytes = read_bytes(&pe.buffer, pos, code_words as usize * 4)?;
+    pos += code_bytes.len();
+
+    let (handler_rva, handler_data_rva) = if has_handler {
+        let handler = read_u32(&pe.buffer, pos)?;
+        (Some(handler), Some(rva.wrapping_add((pos - offset) as u32 + 4)))
+    } else {
+        (None, None)
+    };
+
+    Ok(Arm64XData {
+        version: ((header >> 18) & 0x3) as u8,
+        single_epilog,
+        epilog_count,
+        code_words,
+        epilog_scopes,
+        codes: decode_arm64_codes(code_bytes),
+        handler_rva,
+        handler_data_rva,
+    })
+}
+
+/// Split the ARM64 unwind code bytes into individual codes.
+fn decode_arm64_codes(bytes: &[u8]) -> Vec<Arm64UnwindCode> {
+    let mut codes = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let (name, len) = match b {
+            0x00..=0x1F => ("alloc_s", 1),
+            0x20..=0x3F => ("save_r19r20_x", 1),
+            0x40..=0x7F => ("save_fplr", 1),
+            0x80..=0xBF => ("save_fplr_x", 1),
+            0xC0..=0xC7 => ("alloc_m", 2),
+            0xC8..=0xCB => ("save_regp", 2),
+            0xCC..=0xCF => ("save_regp_x", 2),
+            0xD0..=0xD3 => ("save_reg", 2),
+            0xD4 | 0xD5 => ("save_reg_x", 2),
+            0xD6 | 0xD7 => ("save_lrpair", 2),
+            0xD8 | 0xD9 => ("save_fregp", 2),
+            0xDA | 0xDB => ("save_fregp_x", 2),
+            0xDC | 0xDD => ("save_freg", 2),
+            0xDE => ("save_freg_x", 2),
+            0xDF => ("alloc_z", 2),
+            0xE0 => ("alloc_l", 4),
+            0xE1 => ("set_fp", 1),
+            0xE2 => ("add_fp", 2),
+            0xE3 => ("nop", 1),
+            0xE4 => ("end", 1),
+            0xE5 => ("end_c", 1),
+            0xE6 => ("save_next", 1),
+            0xE7 => ("save_any_reg", 3),
+            0xE8 => ("trap_frame", 1),
+            0xE9 => ("machine_frame", 1),
+            0xEA => ("context", 1),
+            0xEB => ("ec_context", 1),
+            0xEC => ("clear_unwound_to_call", 1),
+            0xFC => ("pac_sign_lr", 1),
+            _ => ("reserved", 1),
+        };
+        let Some(code) = bytes.get(i..i + len) else {
+            break;
+        };
+        codes.push(Arm64UnwindCode { index: i, name, bytes: code.to_vec() });
+        i += len;
+    }
+    codes
+}
@@ -1,10 +1,11 @@
+use std::collections::HashMap;
 use serde::Serialize;
 use crate::{errors::{Error, Result}, parser::PeFile};
-use crate::utils::{rva_to_offset, read_u16, read_u32};
+use crate::utils::{rva_to_offset, read_u16, read_u32, read_c_string, read_dll_names};
 
-/// Represents a single entry in the PE export directory.
+/// Represents the PE export directory (`IMAGE_EXPORT_DIRECTORY`).
 ///
-/// Each entry contains metadata about exported functions, including
+/// It contains metadata about exported functions, including
 /// the DLL name, ordinal base, and RVA addresses of names and functions.
 #[derive(Debug, Serialize)]
 pub struct ExportEntry {
@@ -30,65 +31,130 @@ pub struct ExportEntry {
     pub address_of_name_ordinals: u32,
 }
 
-/// Parses the export table from a PE file and returns a list of entries.
+/// Parses the export directory (`IMAGE_EXPORT_DIRECTORY`) of a PE file.
 ///
-/// This reads the export directory from the first data directory entry (`data_directory[0]`)
-/// and extracts all fixed-size export table structures it can find. The caller is responsible
-/// for resolving RVAs like `name` and `address_of_functions` later if needed.
+/// This reads the single fixed-size directory pointed to by the first data
+/// directory entry (`data_directory[0]`). The caller is responsible for
+/// resolving RVAs like `name` and `address_of_functions`, or can use
+/// [`parse_exports`] to get them resolved.
 ///
 /// # Errors
 ///
-/// Returns [`Error::InvalidTableOffset`] if the export directory’s RVA or size are invalid
+/// Returns [`Error::InvalidTableOffset`] if there is no export directory, if its RVA or size are invalid
 /// or if any of the reads go out of bounds.
-pub fn parse_export_table(pe: &PeFile) -> Result<Vec<ExportEntry>> {
+pub fn parse_export_table(pe: &PeFile) -> Result<ExportEntry> {
     let export_data_directory = pe.optional_header.data_directory()[0];
     let rva  = export_data_directory.virtual_address;
-    let size = export_data_directory.size as usize;
+    if rva == 0 {
+        return Err(Error::InvalidTableOffset);
+    }
 
     let export_table_offset = rva_to_offset(pe, rva)
         .ok_or(Error::InvalidTableOffset)?;
 
-    let slice_bytes: &[u8] = pe.buffer
-        .get(export_table_offset..export_table_offset + size)
+    const DESCRIPTOR_SIZE: usize = 9 * 4 + 2 * 2;
+    let block: &[u8] = pe.buffer
+        .get(export_table_offset..export_table_offset + DESCRIPTOR_SIZE)
         .ok_or(Error::InvalidTableOffset)?;
 
-    const DESCRIPTOR_SIZE: usize = 9 * 4 + 2 * 2;
-    let mut position = 0;
-    let mut export_table_structure = Vec::new();
+    Ok(ExportEntry {
+        characteristics:          read_u32(block, 0)?,
+        time_date_stamp:          read_u32(block, 4)?,
+        major_version:            read_u16(block, 8)?,
+        minor_version:            read_u16(block, 10)?,
+        name:                     read_u32(block, 12)?,
+        base:                     read_u32(block, 16)?,
+        number_of_functions:      read_u32(block, 20)?,
+        number_of_names:          read_u32(block, 24)?,
+        address_of_functions:     read_u32(block, 28)?,
+        address_of_names:         read_u32(block, 32)?,
+        address_of_name_ordinals: read_u32(block, 36)?,
+    })
+}
 
-    while position * DESCRIPTOR_SIZE < slice_bytes.len() {
-        let start = position * DESCRIPTOR_SIZE;
-        let end   = start + DESCRIPTOR_SIZE;
-        let block = &slice_bytes[start..end];
+/// A single exported symbol.
+#[derive(Debug, Serialize)]
+pub struct ExportedSymbol {
+    /// Biased ordinal (`base` + index into the function table).
+    pub ordinal: u32,
+    /// Exported name, if the symbol is exported by name.
+    pub name: Option<String>,
+    /// Further names pointing at the same function, in name table order.
+    pub aliases: Vec<String>,
+    /// RVA of the exported code or data (or of the forwarder string).
+    pub rva: u32,
+    /// Forwarder target such as `"OTHERDLL.Func"` or `"OTHERDLL.#12"`.
+    pub forwarder: Option<String>,
+}
 
-        let characteristics            = read_u32(block, 0)?;
-        let time_date_stamp            = read_u32(block, 4)?;
-        let major_version              = read_u16(block, 8)?;
-        let minor_version              = read_u16(block, 10)?;
-        let name                       = read_u32(block, 12)?;
-        let base                       = read_u32(block, 16)?;
-        let number_of_functions        = read_u32(block, 20)?;
-        let number_of_names            = read_u32(block, 24)?;
-        let address_of_functions       = read_u32(block, 28)?;
-        let address_of_names           = read_u32(block, 32)?;
-        let address_of_name_ordinals   = read_u32(block, 36)?;
+/// Export directory with the DLL name and every exported symbol resolved.
+#[derive(Debug, Serialize)]
+pub struct ExportDirectory {
+    /// Name of the DLL as recorded by the linker.
+    pub dll_name: String,
+    /// Raw `IMAGE_EXPORT_DIRECTORY` fields.
+    pub directory: ExportEntry,
+    /// Exported symbols in ordinal order; unused function slots are skipped.
+    pub symbols: Vec<ExportedSymbol>,
+}
 
-        export_table_structure.push(ExportEntry {
-            characteristics,
-            time_date_stamp,
-            major_version,
-            minor_version,
+/// Parses the export directory and resolves names, ordinals and forwarders.
+///
+/// A function RVA that points back inside the export data directory is a
+/// forwarder: it names a string like `"OTHERDLL.Func"` instead of code.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidTableOffset`] if the directory or any of its
+/// function, name or ordinal arrays fall outside the file data.
+pub fn parse_exports(pe: &PeFile) -> Result<ExportDirectory> {
+    let export_data_directory = pe.optional_header.data_directory()[0];
+    let directory_start = export_data_directory.virtual_address;
+    let directory_end   = directory_start.saturating_add(export_data_directory.size);
+
+    let directory = parse_export_table(pe)?;
+    let dll_name = read_dll_names(pe, directory.name)?;
+
+    // Map function index -> exported names; several names may share an index.
+    let names_offset    = rva_to_offset(pe, directory.address_of_names);
+    let ordinals_offset = rva_to_offset(pe, directory.address_of_name_ordinals);
+    let mut names: HashMap<u32, Vec<String>> = HashMap::new();
+    if directory.number_of_names != 0 {
+        let names_offset    = names_offset.ok_or(Error::InvalidTableOffset)?;
+        let ordinals_offset = ordinals_offset.ok_or(Error::InvalidTableOffset)?;
+        for i in 0..directory.number_of_names as usize {
+            let name_rva = read_u32(&pe.buffer, names_offset + i * 4)?;
+            let index    = read_u16(&pe.buffer, ordinals_offset + i * 2)?;
+            let name     = read_c_string(pe, name_rva)?;
+            names.entry(index as u32).or_default().push(name);
+        }
+    }
+
+    let functions_offset = rva_to_offset(pe, directory.address_of_functions)
+        .ok_or(Error::InvalidTableOffset)?;
+    let mut symbols = Vec::new();
+    for index in 0..directory.number_of_functions {
+        let rva = read_u32(&pe.buffer, functions_offset + index as usize * 4)?;
+        if rva == 0 {
+            continue;
+        }
+
+        let forwarder = if rva >= directory_start && rva < directory_end {
+            Some(read_c_string(pe, rva)?)
+        } else {
+            None
+        };
+
+        let mut aliases = names.remove(&index).unwrap_or_default();
+        let name = (!aliases.is_empty()).then(|| aliases.remove(0));
+        symbols.push(ExportedSymbol {
+            ordinal: directory.base.wrapping_add(index),
             name,
-            base,
-            number_of_functions,
-            number_of_names,
-            address_of_functions,
-            address_of_names,
-            address_of_name_ordinals,
+            aliases,
+            rva,
+            forwarder,
         });
-
-        position += 1;
     }
 
-    Ok(export_table_structure)
+    Ok(ExportDirectory { dll_name, directory, symbols })
 }
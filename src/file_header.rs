@@ -1,24 +1,32 @@
 use crate::headers::FileHeader;
-use std::ptr;
 use crate::errors::Result;
+use crate::utils::{read_bytes, ByteReader};
 
 
 
 impl FileHeader {
+    /// Size of the COFF FileHeader on disk.
+    pub const SIZE: usize = 20;
+
     /// Grab the 20‑byte **COFF FileHeader** that lives right after the PE signature.
     ///
-    // # Safety
+    /// # Errors
     ///
-    /// This does an unaligned, direct memory read of the `#[repr(C)]` struct.
-    /// The caller must guarantee that:
-    /// - `buffer.len() >= std::mem::size_of::<FileHeader>()` (20 bytes),  
-    /// - `buffer` actually begins with a valid File header.
+    /// Returns [`crate::Error::InvalidSize`] if the header runs past the end of
+    /// `buffer` (e.g. a truncated file or a hostile `e_lfanew`).
     pub fn parse_file_header(buffer: &[u8], e_lfanew: usize) -> Result<FileHeader> {
-        let fh_offset = e_lfanew + 4;
-        let header = unsafe {
-            ptr::read_unaligned(buffer.as_ptr().add(fh_offset) as *const FileHeader)
-        };
-        Ok(header)
+        let fh_offset = e_lfanew.saturating_add(4);
+        read_bytes(buffer, fh_offset, Self::SIZE)?;
+
+        let mut r = ByteReader::new(buffer, fh_offset);
+        Ok(FileHeader {
+            machine:                 r.u16()?,
+            number_of_sections:      r.u16()?,
+            time_date_stamp:         r.u32()?,
+            pointer_to_symbol_table: r.u32()?,
+            number_of_symbols:       r.u32()?,
+            size_of_optional_header: r.u16()?,
+            characteristics:         r.u16()?,
+        })
     }
 }
-
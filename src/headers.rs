@@ -8,6 +8,7 @@ pub struct DataDirectory {
 }
 
 pub const PE_SIGNATURE: u32 = 0x00004550;
+pub const DOS_MAGIC: u16 = 0x5A4D;
 
 
 #[repr(C)]
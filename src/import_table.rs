@@ -2,10 +2,13 @@ use serde::Serialize;
 
 use crate::parser::PeFile;
 use crate::errors::{Error, Result};
-use crate::utils::{rva_to_offset, read_u32};
+use crate::utils::{rva_to_offset, read_u16, read_u32, read_u64, read_dll_names, read_c_string};
+
+/// Upper bound on thunks we walk per DLL, in case the array is never terminated.
+const MAX_THUNKS: usize = 0x10000;
 
 /// One entry in the PE import table.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ImportEntry {
     /// RVA of the original import lookup table (first thunk).
     pub original_first_thunk: u32,
@@ -17,6 +20,9 @@ pub struct ImportEntry {
     pub name: u32,
     /// RVA of the import address table (first thunk for IAT).
     pub first_thunk: u32,
+    /// Converted from a delay-load descriptor, see
+    /// [`DelayImportEntry::to_import_entry`](crate::delay_import_table::DelayImportEntry::to_import_entry).
+    pub delay_loaded: bool,
 }
 
 /// Parse the import table from a PE file and return its entries.
@@ -44,7 +50,7 @@ pub fn parse_import_table(pe: &PeFile) -> Result<Vec<ImportEntry>> {
     let mut pos = 0;
 
     // Iterate directory entries until an all-zero terminator.
-    while pos * ENTRY_SIZE < slice.len() {
+    while (pos + 1) * ENTRY_SIZE <= slice.len() {
         let start = pos * ENTRY_SIZE;
         let block = &slice[start..start + ENTRY_SIZE];
 
@@ -70,6 +76,7 @@ pub fn parse_import_table(pe: &PeFile) -> Result<Vec<ImportEntry>> {
             forwarder_chain,
             name,
             first_thunk,
+            delay_loaded: false,
         });
 
         pos += 1;
@@ -77,3 +84,103 @@ pub fn parse_import_table(pe: &PeFile) -> Result<Vec<ImportEntry>> {
 
     Ok(entries)
 }
+
+/// A single function imported through a thunk.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedFunction {
+    /// Index into the exporting DLL's name table (imports by name only).
+    pub hint: Option<u16>,
+    /// Function name (imports by name only). Both `name` and `ordinal` are
+    /// `None` when the hint/name entry doesn't map into the file.
+    pub name: Option<String>,
+    /// Ordinal number (imports by ordinal only).
+    pub ordinal: Option<u16>,
+    /// RVA of the IAT slot the loader patches with the function address.
+    pub iat_rva: u32,
+}
+
+/// An imported DLL together with every function resolved from its thunks.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedDll {
+    /// Name of the imported DLL.
+    pub dll_name: String,
+    /// Raw descriptor this DLL was resolved from.
+    pub descriptor: ImportEntry,
+    /// Functions imported from the DLL, in thunk order.
+    pub functions: Vec<ImportedFunction>,
+}
+
+/// Parse the import table and resolve the DLL name and every thunk of each descriptor.
+///
+/// The lookup table (`original_first_thunk`) is walked when present; otherwise
+/// the IAT itself (`first_thunk`) is used, as in bound or Borland-style images.
+/// A broken thunk array only affects its own DLL; see [`ImportedFunction::name`].
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if the directory or a DLL name does not
+/// map into the file buffer.
+pub fn parse_imports(pe: &PeFile) -> Result<Vec<ImportedDll>> {
+    parse_import_table(pe)?
+        .into_iter()
+        .map(|descriptor| {
+            let dll_name = read_dll_names(pe, descriptor.name)?;
+            let lookup = if descriptor.original_first_thunk != 0 {
+                descriptor.original_first_thunk
+            } else {
+                descriptor.first_thunk
+            };
+            let functions = read_thunks(pe, lookup, descriptor.first_thunk, 0);
+
+            Ok(ImportedDll { dll_name, descriptor, functions })
+        })
+        .collect()
+}
+
+/// Walk a null-terminated thunk array starting at `lookup_rva`.
+///
+/// Thunks are 4 bytes wide in PE32 and 8 bytes in PE32+; the top bit marks an
+/// import by ordinal, otherwise the thunk is the RVA of a hint/name entry.
+/// `iat_rva` is the start of the matching IAT, used to report each slot's RVA.
+/// `name_base` is subtracted from hint/name pointers; it is non-zero only for
+/// tables that store VAs instead of RVAs (old-style delay imports).
+///
+/// The walk stops early, keeping what was read so far, if the array doesn't
+/// map into the file, runs past its end or exceeds [`MAX_THUNKS`] entries.
+pub(crate) fn read_thunks(pe: &PeFile, lookup_rva: u32, iat_rva: u32, name_base: u64) -> Vec<ImportedFunction> {
+    let is_64 = pe.optional_header.is_64();
+    let (width, ordinal_flag) = if is_64 { (8usize, 1u64 << 63) } else { (4usize, 1u64 << 31) };
+
+    let mut functions = Vec::new();
+    let Some(offset) = rva_to_offset(pe, lookup_rva) else {
+        return functions;
+    };
+
+    for index in 0..MAX_THUNKS {
+        let thunk_offset = offset + index * width;
+        let thunk = if is_64 {
+            read_u64(&pe.buffer, thunk_offset)
+        } else {
+            read_u32(&pe.buffer, thunk_offset).map(u64::from)
+        };
+        let Ok(thunk) = thunk else {
+            break;
+        };
+        if thunk == 0 {
+            break;
+        }
+
+        let iat_rva = iat_rva.wrapping_add((index * width) as u32);
+        let function = if thunk & ordinal_flag != 0 {
+            ImportedFunction { hint: None, name: None, ordinal: Some(thunk as u16), iat_rva }
+        } else {
+            // Hint/name entry: u16 hint followed by a null-terminated name.
+            let hint_rva = (thunk.wrapping_sub(name_base) & 0x7FFF_FFFF) as u32;
+            let hint = rva_to_offset(pe, hint_rva).and_then(|offset| read_u16(&pe.buffer, offset).ok());
+            let name = hint.and_then(|_| read_c_string(pe, hint_rva + 2).ok());
+            ImportedFunction { hint, name, ordinal: None, iat_rva }
+        };
+        functions.push(function);
+    }
+
+    functions
+}
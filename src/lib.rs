@@ -2,14 +2,40 @@ pub mod headers;
 pub mod parser;
 pub mod errors;
 pub mod import_table;
+pub mod delay_import_table;
+pub mod bound_import_table;
 pub mod utils;
 pub mod export_table;
+pub mod resource_table;
+pub mod version_info;
+pub mod relocation_table;
+pub mod tls_directory;
+pub mod load_config;
+pub mod debug_directory;
+pub mod exception_table;
+pub mod certificate_table;
+pub mod pkcs7;
+pub mod authentihash;
+pub mod checksum;
+pub mod dotnet;
+pub mod metadata_tables;
+pub mod rich_header;
+pub mod fingerprint;
+pub mod entropy;
+pub mod overlay;
+mod der;
 pub mod dos_header;
 pub mod file_header;
 pub mod section_header;
 pub mod optional_header;
 pub mod view;
+pub mod buffer;
+pub mod anomalies;
+pub mod address;
 
 pub use errors::{Error, Result};
+pub use address::Mapping;
+pub use anomalies::{Anomaly, AnomalyKind, Severity};
+pub use buffer::PeBuffer;
 pub use parser::PeFile;
 pub use view::Parsed;
\ No newline at end of file
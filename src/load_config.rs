@@ -0,0 +1,355 @@
+use serde::Serialize;
+
+use crate::errors::{Error, Result};
+use crate::parser::PeFile;
+use crate::utils::{read_u32, read_u8, rva_to_offset, ByteReader};
+
+/// Mask of `guard_flags` giving the number of extra bytes after each GFIDS entry.
+const GUARD_CF_FUNCTION_TABLE_SIZE_MASK: u32 = 0xF000_0000;
+const GUARD_CF_FUNCTION_TABLE_SIZE_SHIFT: u32 = 28;
+
+/// An entry of a guard table (CF functions, address-taken IAT entries,
+/// long jump targets or EH continuation targets).
+#[derive(Debug, Clone, Serialize)]
+pub struct GuardEntry {
+    pub rva: u32,
+    /// First metadata byte following the RVA (0 if the table has none).
+    pub flags: u8,
+    /// Names of the `IMAGE_GUARD_FLAG_*` bits set in `flags`.
+    pub flag_names: Vec<&'static str>,
+}
+
+/// Header of the dynamic value relocation table (`IMAGE_DYNAMIC_RELOCATION_TABLE`).
+#[derive(Debug, Clone, Serialize)]
+pub struct DynamicRelocationTable {
+    pub rva: u32,
+    pub version: u32,
+    pub size: u32,
+}
+
+/// Decoded `IMAGE_LOAD_CONFIG_DIRECTORY32` / `IMAGE_LOAD_CONFIG_DIRECTORY64`.
+///
+/// The structure has grown with almost every Windows release, so every field
+/// past `size` is optional: it is `None` when the file's structure is too old
+/// to contain it. Pointer fields are virtual addresses, widened to `u64`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LoadConfig {
+    pub size: u32,
+    pub time_date_stamp: Option<u32>,
+    pub major_version: Option<u16>,
+    pub minor_version: Option<u16>,
+    pub global_flags_clear: Option<u32>,
+    pub global_flags_set: Option<u32>,
+    pub critical_section_default_timeout: Option<u32>,
+    pub de_commit_free_block_threshold: Option<u64>,
+    pub de_commit_total_free_threshold: Option<u64>,
+    pub lock_prefix_table: Option<u64>,
+    pub maximum_allocation_size: Option<u64>,
+    pub virtual_memory_threshold: Option<u64>,
+    pub process_affinity_mask: Option<u64>,
+    pub process_heap_flags: Option<u32>,
+    pub csd_version: Option<u16>,
+    pub dependent_load_flags: Option<u16>,
+    pub edit_list: Option<u64>,
+    pub security_cookie: Option<u64>,
+    pub se_handler_table: Option<u64>,
+    pub se_handler_count: Option<u64>,
+    pub guard_cf_check_function_pointer: Option<u64>,
+    pub guard_cf_dispatch_function_pointer: Option<u64>,
+    pub guard_cf_function_table: Option<u64>,
+    pub guard_cf_function_count: Option<u64>,
+    pub guard_flags: Option<u32>,
+    pub code_integrity_flags: Option<u16>,
+    pub code_integrity_catalog: Option<u16>,
+    pub code_integrity_catalog_offset: Option<u32>,
+    pub code_integrity_reserved: Option<u32>,
+    pub guard_address_taken_iat_entry_table: Option<u64>,
+    pub guard_address_taken_iat_entry_count: Option<u64>,
+    pub guard_long_jump_target_table: Option<u64>,
+    pub guard_long_jump_target_count: Option<u64>,
+    pub dynamic_value_reloc_table: Option<u64>,
+    pub chpe_metadata_pointer: Option<u64>,
+    pub guard_rf_failure_routine: Option<u64>,
+    pub guard_rf_failure_routine_function_pointer: Option<u64>,
+    pub dynamic_value_reloc_table_offset: Option<u32>,
+    pub dynamic_value_reloc_table_section: Option<u16>,
+    pub reserved2: Option<u16>,
+    pub guard_rf_verify_stack_pointer_function_pointer: Option<u64>,
+    pub hot_patch_table_offset: Option<u32>,
+    pub reserved3: Option<u32>,
+    pub enclave_configuration_pointer: Option<u64>,
+    pub volatile_metadata_pointer: Option<u64>,
+    pub guard_eh_continuation_table: Option<u64>,
+    pub guard_eh_continuation_count: Option<u64>,
+    pub guard_xfg_check_function_pointer: Option<u64>,
+    pub guard_xfg_dispatch_function_pointer: Option<u64>,
+    pub guard_xfg_table_dispatch_function_pointer: Option<u64>,
+    pub cast_guard_os_determined_failure_mode: Option<u64>,
+    pub guard_memcpy_function_pointer: Option<u64>,
+
+    /// Names of the `IMAGE_GUARD_*` bits set in `guard_flags`.
+    pub guard_flag_names: Vec<&'static str>,
+    /// SafeSEH handler RVAs (PE32 only).
+    pub se_handlers: Vec<u32>,
+    /// Control Flow Guard valid call targets.
+    pub guard_cf_functions: Vec<GuardEntry>,
+    /// IAT entries whose address is taken.
+    pub guard_address_taken_iat_entries: Vec<GuardEntry>,
+    /// Valid `longjmp` targets.
+    pub guard_long_jump_targets: Vec<GuardEntry>,
+    /// Valid EH continuation targets (CET shadow stack).
+    pub guard_eh_continuations: Vec<GuardEntry>,
+    /// RVA of the CHPE (hybrid ARM64/x86) metadata.
+    pub chpe_metadata_rva: Option<u32>,
+    /// RVA of the volatile metadata.
+    pub volatile_metadata_rva: Option<u32>,
+    /// Dynamic value relocation table header, located through either its
+    /// pointer or its section + offset pair.
+    pub dynamic_relocations: Option<DynamicRelocationTable>,
+}
+
+/// Reads fields of a structure whose real length is given by its `Size` field,
+/// yielding `None` for fields that lie past it.
+struct SizedReader<'a> {
+    r: ByteReader<'a>,
+    end: usize,
+    is_64: bool,
+}
+
+impl SizedReader<'_> {
+    /// Once a field doesn't fit, every later field is absent too.
+    fn fits(&mut self, width: usize) -> bool {
+        if self.r.position() + width > self.end {
+            self.end = self.r.position();
+        }
+        self.r.position() + width <= self.end
+    }
+
+    fn u16(&mut self) -> Result<Option<u16>> {
+        if self.fits(2) { self.r.u16().map(Some) } else { Ok(None) }
+    }
+
+    fn u32(&mut self) -> Result<Option<u32>> {
+        if self.fits(4) { self.r.u32().map(Some) } else { Ok(None) }
+    }
+
+    /// Pointer-sized field (`ULONGLONG` in PE32+, `DWORD` in PE32).
+    fn ptr(&mut self) -> Result<Option<u64>> {
+        if self.is_64 {
+            if self.fits(8) { self.r.u64().map(Some) } else { Ok(None) }
+        } else {
+            self.u32().map(|v| v.map(u64::from))
+        }
+    }
+}
+
+/// Parse the load configuration directory (data directory 10).
+///
+/// The structure's own `size` field decides which fields are present, so
+/// every historical layout (from the original XP-era structure up to XFG and
+/// memcpy guard) is handled. The SafeSEH, guard CF, address-taken IAT, long
+/// jump and EH continuation tables are decoded along with it.
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if the directory is absent or any of the
+/// tables it references does not map into the file, or `Error::InvalidSize`
+/// if one of them is truncated.
+pub fn parse_load_config(pe: &PeFile) -> Result<LoadConfig> {
+    let directory = pe.optional_header.data_directory()[10];
+    if directory.virtual_address == 0 {
+        return Err(Error::InvalidTableOffset);
+    }
+    let offset = rva_to_offset(pe, directory.virtual_address)
+        .ok_or(Error::InvalidTableOffset)?;
+
+    let size = read_u32(&pe.buffer, offset)?;
+    let mut r = SizedReader {
+        r: ByteReader::new(&pe.buffer, offset + 4),
+        end: offset + size as usize,
+        is_64: pe.optional_header.is_64(),
+    };
+
+    let time_date_stamp                  = r.u32()?;
+    let major_version                    = r.u16()?;
+    let minor_version                    = r.u16()?;
+    let global_flags_clear               = r.u32()?;
+    let global_flags_set                 = r.u32()?;
+    let critical_section_default_timeout = r.u32()?;
+    let de_commit_free_block_threshold   = r.ptr()?;
+    let de_commit_total_free_threshold   = r.ptr()?;
+    let lock_prefix_table                = r.ptr()?;
+    let maximum_allocation_size          = r.ptr()?;
+    let virtual_memory_threshold         = r.ptr()?;
+    // The 32-bit structure stores these two the other way round.
+    let (process_affinity_mask, process_heap_flags) = if r.is_64 {
+        (r.ptr()?, r.u32()?)
+    } else {
+        let heap_flags = r.u32()?;
+        (r.ptr()?, heap_flags)
+    };
+
+    let mut config = LoadConfig {
+        size,
+        time_date_stamp,
+        major_version,
+        minor_version,
+        global_flags_clear,
+        global_flags_set,
+        critical_section_default_timeout,
+        de_commit_free_block_threshold,
+        de_commit_total_free_threshold,
+        lock_prefix_table,
+        maximum_allocation_size,
+        virtual_memory_threshold,
+        process_affinity_mask,
+        process_heap_flags,
+        csd_version: r.u16()?,
+        dependent_load_flags: r.u16()?,
+        edit_list: r.ptr()?,
+        security_cookie: r.ptr()?,
+        se_handler_table: r.ptr()?,
+        se_handler_count: r.ptr()?,
+        guard_cf_check_function_pointer: r.ptr()?,
+        guard_cf_dispatch_function_pointer: r.ptr()?,
+        guard_cf_function_table: r.ptr()?,
+        guard_cf_function_count: r.ptr()?,
+        guard_flags: r.u32()?,
+        code_integrity_flags: r.u16()?,
+        code_integrity_catalog: r.u16()?,
+        code_integrity_catalog_offset: r.u32()?,
+        code_integrity_reserved: r.u32()?,
+        guard_address_taken_iat_entry_table: r.ptr()?,
+        guard_address_taken_iat_entry_count: r.ptr()?,
+        guard_long_jump_target_table: r.ptr()?,
+        guard_long_jump_target_count: r.ptr()?,
+        dynamic_value_reloc_table: r.ptr()?,
+        chpe_metadata_pointer: r.ptr()?,
+        guard_rf_failure_routine: r.ptr()?,
+        guard_rf_failure_routine_function_pointer: r.ptr()?,
+        dynamic_value_reloc_table_offset: r.u32()?,
+        dynamic_value_reloc_table_section: r.u16()?,
+        reserved2: r.u16()?,
+        guard_rf_verify_stack_pointer_function_pointer: r.ptr()?,
+        hot_patch_table_offset: r.u32()?,
+        reserved3: r.u32()?,
+        enclave_configuration_pointer: r.ptr()?,
+        volatile_metadata_pointer: r.ptr()?,
+        guard_eh_continuation_table: r.ptr()?,
+        guard_eh_continuation_count: r.ptr()?,
+        guard_xfg_check_function_pointer: r.ptr()?,
+        guard_xfg_dispatch_function_pointer: r.ptr()?,
+        guard_xfg_table_dispatch_function_pointer: r.ptr()?,
+        cast_guard_os_determined_failure_mode: r.ptr()?,
+        guard_memcpy_function_pointer: r.ptr()?,
+        ..LoadConfig::default()
+    };
+
+    let guard_flags = config.guard_flags.unwrap_or(0);
+    config.guard_flag_names = guard_flag_names(guard_flags);
+
+    // SafeSEH only exists for x86 images: a table of 32-bit handler RVAs.
+    if !pe.optional_header.is_64() {
+        if let (Some(table), Some(count)) = (config.se_handler_table, config.se_handler_count) {
+            let entries = read_guard_table(pe, table, count, 0)?;
+            config.se_handlers = entries.into_iter().map(|entry| entry.rva).collect();
+        }
+    }
+
+    let stride = ((guard_flags & GUARD_CF_FUNCTION_TABLE_SIZE_MASK) >> GUARD_CF_FUNCTION_TABLE_SIZE_SHIFT) as usize;
+    let table = |table: Option<u64>, count: Option<u64>| match (table, count) {
+        (Some(table), Some(count)) => read_guard_table(pe, table, count, stride),
+        _ => Ok(Vec::new()),
+    };
+    config.guard_cf_functions = table(config.guard_cf_function_table, config.guard_cf_function_count)?;
+    config.guard_address_taken_iat_entries =
+        table(config.guard_address_taken_iat_entry_table, config.guard_address_taken_iat_entry_count)?;
+    config.guard_long_jump_targets = table(config.guard_long_jump_target_table, config.guard_long_jump_target_count)?;
+    config.guard_eh_continuations = table(config.guard_eh_continuation_table, config.guard_eh_continuation_count)?;
+
+    config.chpe_metadata_rva = config.chpe_metadata_pointer.filter(|&va| va != 0).and_then(|va| pe.va_to_rva(va));
+    config.volatile_metadata_rva = config.volatile_metadata_pointer.filter(|&va| va != 0).and_then(|va| pe.va_to_rva(va));
+    config.dynamic_relocations = dynamic_relocation_table(pe, &config);
+
+    Ok(config)
+}
+
+/// Read `count` entries of a guard table at virtual address `table`.
+///
+/// Each entry is a 32-bit RVA followed by `extra` metadata bytes.
+fn read_guard_table(pe: &PeFile, table: u64, count: u64, extra: usize) -> Result<Vec<GuardEntry>> {
+    if table == 0 || count == 0 {
+        return Ok(Vec::new());
+    }
+    let offset = pe.va_to_rva(table)
+        .and_then(|rva| rva_to_offset(pe, rva))
+        .ok_or(Error::InvalidTableOffset)?;
+
+    let stride = 4 + extra;
+    let mut entries = Vec::new();
+    for index in 0..count {
+        let at = offset + index as usize * stride;
+        let rva = read_u32(&pe.buffer, at)?;
+        let flags = if extra > 0 { read_u8(&pe.buffer, at + 4)? } else { 0 };
+        entries.push(GuardEntry { rva, flags, flag_names: guard_entry_flag_names(flags) });
+    }
+    Ok(entries)
+}
+
+fn dynamic_relocation_table(pe: &PeFile, config: &LoadConfig) -> Option<DynamicRelocationTable> {
+    let rva = match (config.dynamic_value_reloc_table_section, config.dynamic_value_reloc_table_offset) {
+        // The section number is 1-based.
+        (Some(section), Some(offset)) if section != 0 => {
+            pe.sections.get(section as usize - 1)?.virtual_address.checked_add(offset)?
+        }
+        _ => pe.va_to_rva(config.dynamic_value_reloc_table.filter(|&va| va != 0)?)?,
+    };
+    let offset = rva_to_offset(pe, rva)?;
+    Some(DynamicRelocationTable {
+        rva,
+        version: read_u32(&pe.buffer, offset).ok()?,
+        size: read_u32(&pe.buffer, offset + 4).ok()?,
+    })
+}
+
+fn guard_flag_names(flags: u32) -> Vec<&'static str> {
+    let flag_defs = [
+        (0x0000_0100, "CF_INSTRUMENTED"),
+        (0x0000_0200, "CFW_INSTRUMENTED"),
+        (0x0000_0400, "CF_FUNCTION_TABLE_PRESENT"),
+        (0x0000_0800, "SECURITY_COOKIE_UNUSED"),
+        (0x0000_1000, "PROTECT_DELAYLOAD_IAT"),
+        (0x0000_2000, "DELAYLOAD_IAT_IN_ITS_OWN_SECTION"),
+        (0x0000_4000, "CF_EXPORT_SUPPRESSION_INFO_PRESENT"),
+        (0x0000_8000, "CF_ENABLE_EXPORT_SUPPRESSION"),
+        (0x0001_0000, "CF_LONGJUMP_TABLE_PRESENT"),
+        (0x0002_0000, "RF_INSTRUMENTED"),
+        (0x0004_0000, "RF_ENABLE"),
+        (0x0008_0000, "RF_STRICT"),
+        (0x0010_0000, "RETPOLINE_PRESENT"),
+        (0x0040_0000, "EH_CONTINUATION_TABLE_PRESENT"),
+        (0x0080_0000, "XFG_ENABLED"),
+        (0x0100_0000, "CASTGUARD_PRESENT"),
+        (0x0200_0000, "MEMCPY_PRESENT"),
+    ];
+
+    flag_defs
+        .iter()
+        .filter(|(mask, _)| flags & mask != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+fn guard_entry_flag_names(flags: u8) -> Vec<&'static str> {
+    let flag_defs = [
+        (0x01, "FID_SUPPRESSED"),
+        (0x02, "EXPORT_SUPPRESSED"),
+        (0x04, "FID_LANGEXCPTHANDLER"),
+        (0x08, "FID_XFG"),
+    ];
+
+    flag_defs
+        .iter()
+        .filter(|(mask, _)| flags & mask != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
@@ -0,0 +1,690 @@
+use serde::Serialize;
+
+use crate::dotnet::MetadataRoot;
+use crate::errors::{Error, Result};
+use crate::parser::PeFile;
+use crate::utils::{read_bytes, read_u16, read_u32, read_u64, read_u8};
+
+const MODULE: u8 = 0x00;
+const TYPE_REF: u8 = 0x01;
+const TYPE_DEF: u8 = 0x02;
+const FIELD: u8 = 0x04;
+const METHOD_DEF: u8 = 0x06;
+const PARAM: u8 = 0x08;
+const MEMBER_REF: u8 = 0x0A;
+const CUSTOM_ATTRIBUTE: u8 = 0x0C;
+const MODULE_REF: u8 = 0x1A;
+const TYPE_SPEC: u8 = 0x1B;
+const IMPL_MAP: u8 = 0x1C;
+const ASSEMBLY: u8 = 0x20;
+const ASSEMBLY_REF: u8 = 0x23;
+const MANIFEST_RESOURCE: u8 = 0x28;
+
+/// Number of tables defined by ECMA-335; later ids belong to portable PDBs.
+const TABLE_COUNT: usize = 0x2D;
+/// Marks an unused tag in a coded index.
+const NONE: u8 = 0xFF;
+
+/// Column kinds of the metadata table schema.
+#[derive(Clone, Copy)]
+enum Col {
+    U16,
+    U32,
+    Str,
+    Guid,
+    Blob,
+    /// Simple index into another table.
+    Table(u8),
+    /// Coded index: tag width in bits and the tables selected by each tag.
+    Coded(u32, &'static [u8]),
+}
+
+const TYPE_DEF_OR_REF: Col = Col::Coded(2, &[TYPE_DEF, TYPE_REF, TYPE_SPEC]);
+const HAS_CONSTANT: Col = Col::Coded(2, &[FIELD, PARAM, 0x17]);
+const HAS_CUSTOM_ATTRIBUTE: Col = Col::Coded(5, &[
+    METHOD_DEF, FIELD, TYPE_REF, TYPE_DEF, PARAM, 0x09, MEMBER_REF, MODULE, 0x0E, 0x17, 0x14,
+    0x11, MODULE_REF, TYPE_SPEC, ASSEMBLY, ASSEMBLY_REF, 0x26, 0x27, MANIFEST_RESOURCE, 0x2A,
+    0x2C, 0x2B,
+]);
+const HAS_FIELD_MARSHAL: Col = Col::Coded(1, &[FIELD, PARAM]);
+const HAS_DECL_SECURITY: Col = Col::Coded(2, &[TYPE_DEF, METHOD_DEF, ASSEMBLY]);
+const MEMBER_REF_PARENT: Col = Col::Coded(3, &[TYPE_DEF, TYPE_REF, MODULE_REF, METHOD_DEF, TYPE_SPEC]);
+const HAS_SEMANTICS: Col = Col::Coded(1, &[0x14, 0x17]);
+const METHOD_DEF_OR_REF: Col = Col::Coded(1, &[METHOD_DEF, MEMBER_REF]);
+const MEMBER_FORWARDED: Col = Col::Coded(1, &[FIELD, METHOD_DEF]);
+const IMPLEMENTATION: Col = Col::Coded(2, &[0x26, ASSEMBLY_REF, 0x27]);
+const CUSTOM_ATTRIBUTE_TYPE: Col = Col::Coded(3, &[NONE, NONE, METHOD_DEF, MEMBER_REF, NONE]);
+const RESOLUTION_SCOPE: Col = Col::Coded(2, &[MODULE, MODULE_REF, ASSEMBLY_REF, TYPE_REF]);
+const TYPE_OR_METHOD_DEF: Col = Col::Coded(1, &[TYPE_DEF, METHOD_DEF]);
+
+use Col::{Blob, Guid, Str, Table, U16, U32};
+
+/// Columns of every table, indexed by table id (ECMA-335 II.22).
+const SCHEMA: [(&str, &[Col]); TABLE_COUNT] = [
+    ("Module", &[U16, Str, Guid, Guid, Guid]),
+    ("TypeRef", &[RESOLUTION_SCOPE, Str, Str]),
+    ("TypeDef", &[U32, Str, Str, TYPE_DEF_OR_REF, Table(FIELD), Table(METHOD_DEF)]),
+    ("FieldPtr", &[Table(FIELD)]),
+    ("Field", &[U16, Str, Blob]),
+    ("MethodPtr", &[Table(METHOD_DEF)]),
+    ("MethodDef", &[U32, U16, U16, Str, Blob, Table(PARAM)]),
+    ("ParamPtr", &[Table(PARAM)]),
+    ("Param", &[U16, U16, Str]),
+    ("InterfaceImpl", &[Table(TYPE_DEF), TYPE_DEF_OR_REF]),
+    ("MemberRef", &[MEMBER_REF_PARENT, Str, Blob]),
+    ("Constant", &[U16, HAS_CONSTANT, Blob]),
+    ("CustomAttribute", &[HAS_CUSTOM_ATTRIBUTE, CUSTOM_ATTRIBUTE_TYPE, Blob]),
+    ("FieldMarshal", &[HAS_FIELD_MARSHAL, Blob]),
+    ("DeclSecurity", &[U16, HAS_DECL_SECURITY, Blob]),
+    ("ClassLayout", &[U16, U32, Table(TYPE_DEF)]),
+    ("FieldLayout", &[U32, Table(FIELD)]),
+    ("StandAloneSig", &[Blob]),
+    ("EventMap", &[Table(TYPE_DEF), Table(0x14)]),
+    ("EventPtr", &[Table(0x14)]),
+    ("Event", &[U16, Str, TYPE_DEF_OR_REF]),
+    ("PropertyMap", &[Table(TYPE_DEF), Table(0x17)]),
+    ("PropertyPtr", &[Table(0x17)]),
+    ("Property", &[U16, Str, Blob]),
+    ("MethodSemantics", &[U16, Table(METHOD_DEF), HAS_SEMANTICS]),
+    ("MethodImpl", &[Table(TYPE_DEF), METHOD_DEF_OR_REF, METHOD_DEF_OR_REF]),
+    ("ModuleRef", &[Str]),
+    ("TypeSpec", &[Blob]),
+    ("ImplMap", &[U16, MEMBER_FORWARDED, Str, Table(MODULE_REF)]),
+    ("FieldRVA", &[U32, Table(FIELD)]),
+    ("EncLog", &[U32, U32]),
+    ("EncMap", &[U32]),
+    ("Assembly", &[U32, U16, U16, U16, U16, U32, Blob, Str, Str]),
+    ("AssemblyProcessor", &[U32]),
+    ("AssemblyOS", &[U32, U32, U32]),
+    ("AssemblyRef", &[U16, U16, U16, U16, U32, Blob, Str, Str, Blob]),
+    ("AssemblyRefProcessor", &[U32, Table(ASSEMBLY_REF)]),
+    ("AssemblyRefOS", &[U32, U32, U32, Table(ASSEMBLY_REF)]),
+    ("File", &[U32, Str, Blob]),
+    ("ExportedType", &[U32, U32, Str, Str, IMPLEMENTATION]),
+    ("ManifestResource", &[U32, U32, Str, IMPLEMENTATION]),
+    ("NestedClass", &[Table(TYPE_DEF), Table(TYPE_DEF)]),
+    ("GenericParam", &[U16, U16, TYPE_OR_METHOD_DEF, Str]),
+    ("MethodSpec", &[METHOD_DEF_OR_REF, Blob]),
+    ("GenericParamConstraint", &[Table(0x2A), TYPE_DEF_OR_REF]),
+];
+
+/// Row count of one present table.
+#[derive(Debug, Clone, Serialize)]
+pub struct TableInfo {
+    pub id: u8,
+    pub name: &'static str,
+    pub rows: u32,
+}
+
+/// A row of the `Module` table.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleDef {
+    pub generation: u16,
+    pub name: String,
+    /// Module version id, formatted as a GUID.
+    pub mvid: Option<String>,
+}
+
+/// A row of the `TypeRef` table: a type defined in another module or assembly.
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeRef {
+    /// Token of the `Module`, `ModuleRef`, `AssemblyRef` or enclosing `TypeRef`.
+    pub resolution_scope: u32,
+    pub namespace: String,
+    pub name: String,
+}
+
+/// A row of the `TypeDef` table.
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeDef {
+    pub flags: u32,
+    pub namespace: String,
+    pub name: String,
+    /// Full name of the base type, if it is a `TypeDef` or `TypeRef`.
+    pub extends: Option<String>,
+    /// First row of this type's fields in the `Field` table.
+    pub field_list: u32,
+    /// First row of this type's methods in the `MethodDef` table.
+    pub method_list: u32,
+}
+
+/// A row of the `Field` table.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldDef {
+    pub flags: u16,
+    pub name: String,
+    /// Full name of the owning type.
+    pub declaring_type: Option<String>,
+}
+
+/// A row of the `MethodDef` table.
+#[derive(Debug, Clone, Serialize)]
+pub struct MethodDef {
+    /// RVA of the method body; 0 for abstract, runtime or P/Invoke methods.
+    pub rva: u32,
+    pub impl_flags: u16,
+    pub flags: u16,
+    pub name: String,
+    /// Full name of the owning type.
+    pub declaring_type: Option<String>,
+    /// First row of this method's parameters in the `Param` table.
+    pub param_list: u32,
+}
+
+/// A row of the `Param` table.
+#[derive(Debug, Clone, Serialize)]
+pub struct ParamDef {
+    pub flags: u16,
+    /// 1-based position; 0 describes the return value.
+    pub sequence: u16,
+    pub name: String,
+}
+
+/// A row of the `MemberRef` table: a field or method of another type.
+#[derive(Debug, Clone, Serialize)]
+pub struct MemberRef {
+    /// Token of the parent (`TypeDef`, `TypeRef`, `ModuleRef`, `MethodDef` or `TypeSpec`).
+    pub parent: u32,
+    /// Full name of the parent type, when it is a `TypeDef` or `TypeRef`.
+    pub parent_name: Option<String>,
+    pub name: String,
+}
+
+/// A row of the `CustomAttribute` table.
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomAttribute {
+    /// Token of the metadata item the attribute is applied to.
+    pub parent: u32,
+    /// Token of the attribute constructor (`MethodDef` or `MemberRef`).
+    pub constructor: u32,
+    /// Full name of the attribute type.
+    pub attribute_type: Option<String>,
+    /// Size of the encoded constructor arguments.
+    pub value_size: usize,
+}
+
+/// The row of the `Assembly` table.
+#[derive(Debug, Clone, Serialize)]
+pub struct AssemblyDef {
+    pub hash_alg_id: u32,
+    /// `major.minor.build.revision`.
+    pub version: String,
+    pub flags: u32,
+    /// Public key as uppercase hex; empty if the assembly is not strong-named.
+    pub public_key: String,
+    pub name: String,
+    pub culture: String,
+}
+
+/// A row of the `AssemblyRef` table: a referenced assembly.
+#[derive(Debug, Clone, Serialize)]
+pub struct AssemblyRef {
+    /// `major.minor.build.revision`.
+    pub version: String,
+    pub flags: u32,
+    /// Public key or its 8-byte token, as uppercase hex.
+    pub public_key_or_token: String,
+    pub name: String,
+    pub culture: String,
+}
+
+/// A row of the `ManifestResource` table.
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestResource {
+    /// Offset into the CLR header's resources directory, for embedded resources.
+    pub offset: u32,
+    pub flags: u32,
+    pub name: String,
+    /// Token of the `File` or `AssemblyRef` holding the resource; `None` if
+    /// it is embedded in this image.
+    pub implementation: Option<u32>,
+}
+
+/// A row of the `ImplMap` table: a P/Invoke import.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImplMap {
+    pub mapping_flags: u16,
+    /// Name of the managed method (or field) the import is bound to.
+    pub member: String,
+    /// Name of the native function.
+    pub import_name: String,
+    /// Native module, from the `ModuleRef` table.
+    pub module: String,
+}
+
+/// Decoded `#~` (or uncompressed `#-`) metadata tables stream.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetadataTables {
+    pub major_version: u8,
+    pub minor_version: u8,
+    /// Heap index width flags: 0x01 `#Strings`, 0x02 `#GUID`, 0x04 `#Blob`.
+    pub heap_sizes: u8,
+    /// Every present table with its row count.
+    pub tables: Vec<TableInfo>,
+    pub module: Option<ModuleDef>,
+    pub type_refs: Vec<TypeRef>,
+    pub type_defs: Vec<TypeDef>,
+    pub fields: Vec<FieldDef>,
+    pub methods: Vec<MethodDef>,
+    pub params: Vec<ParamDef>,
+    pub member_refs: Vec<MemberRef>,
+    pub custom_attributes: Vec<CustomAttribute>,
+    pub module_refs: Vec<String>,
+    pub impl_maps: Vec<ImplMap>,
+    pub assembly: Option<AssemblyDef>,
+    pub assembly_refs: Vec<AssemblyRef>,
+    pub manifest_resources: Vec<ManifestResource>,
+}
+
+/// Decode the metadata tables stream referenced by `metadata`.
+///
+/// Column widths follow the stream's heap-size flags and the row counts of
+/// the tables each simple or coded index can point into.
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if there is no `#~` or `#-` stream,
+/// `Error::InvalidSize` if it is truncated, or `Error::InvalidData` if an
+/// index points outside its heap or table.
+pub fn parse_metadata_tables(pe: &PeFile, metadata: &MetadataRoot) -> Result<MetadataTables> {
+    let stream = metadata
+        .stream("#~")
+        .or_else(|| metadata.stream("#-"))
+        .ok_or(Error::InvalidTableOffset)?;
+    let heap = |name: &str| -> Result<&[u8]> {
+        metadata.stream(name).map_or(Ok(&[][..]), |stream| stream.data(pe))
+    };
+    let db = Database::new(
+        stream.data(pe)?,
+        heap("#Strings")?,
+        heap("#GUID")?,
+        heap("#Blob")?,
+    )?;
+    db.decode()
+}
+
+/// Layout of the tables stream plus the heaps its columns index into.
+struct Database<'a> {
+    stream: &'a [u8],
+    strings: &'a [u8],
+    guids: &'a [u8],
+    blobs: &'a [u8],
+    major_version: u8,
+    minor_version: u8,
+    heap_sizes: u8,
+    rows: [u32; TABLE_COUNT],
+    /// Stream offset and row size of each table whose position is known.
+    layout: [Option<(usize, usize)>; TABLE_COUNT],
+}
+
+impl<'a> Database<'a> {
+    fn new(stream: &'a [u8], strings: &'a [u8], guids: &'a [u8], blobs: &'a [u8]) -> Result<Self> {
+        let major_version = read_u8(stream, 4)?;
+        let minor_version = read_u8(stream, 5)?;
+        let heap_sizes = read_u8(stream, 6)?;
+        let valid = read_u64(stream, 8)?;
+
+        let mut rows = [0u32; TABLE_COUNT];
+        let mut pos = 24;
+        for id in 0..64 {
+            if valid & (1u64 << id) == 0 {
+                continue;
+            }
+            let count = read_u32(stream, pos)?;
+            pos += 4;
+            // Portable PDB tables come after all of ours and are not decoded.
+            if let Some(slot) = rows.get_mut(id) {
+                *slot = count;
+            }
+        }
+        // Uncompressed streams written by edit-and-continue carry 4 extra bytes.
+        if heap_sizes & 0x40 != 0 {
+            pos += 4;
+        }
+
+        let mut db = Database {
+            stream,
+            strings,
+            guids,
+            blobs,
+            major_version,
+            minor_version,
+            heap_sizes,
+            rows,
+            layout: [None; TABLE_COUNT],
+        };
+        for (id, (_, columns)) in SCHEMA.iter().enumerate() {
+            if valid & (1u64 << id) == 0 {
+                continue;
+            }
+            let row_size: usize = columns.iter().map(|&col| db.width(col)).sum();
+            db.layout[id] = Some((pos, row_size));
+            pos = pos
+                .checked_add(row_size.checked_mul(db.rows[id] as usize).ok_or(Error::InvalidData)?)
+                .ok_or(Error::InvalidData)?;
+        }
+        Ok(db)
+    }
+
+    fn width(&self, col: Col) -> usize {
+        let wide = |flag: u8| if self.heap_sizes & flag != 0 { 4 } else { 2 };
+        match col {
+            Col::U16 => 2,
+            Col::U32 => 4,
+            Col::Str => wide(0x01),
+            Col::Guid => wide(0x02),
+            Col::Blob => wide(0x04),
+            Col::Table(id) => if self.rows[id as usize] < 0x1_0000 { 2 } else { 4 },
+            Col::Coded(bits, tables) => {
+                let max = tables
+                    .iter()
+                    .filter(|&&id| id != NONE)
+                    .map(|&id| self.rows[id as usize])
+                    .max()
+                    .unwrap_or(0);
+                if max < 1 << (16 - bits) { 2 } else { 4 }
+            }
+        }
+    }
+
+    /// Column values of a 1-based row. Simple indexes are returned as row
+    /// numbers, coded indexes as metadata tokens.
+    fn row(&self, table: u8, index: u32) -> Result<Vec<u32>> {
+        let (start, row_size) = self.layout[table as usize].ok_or(Error::InvalidTableOffset)?;
+        if index == 0 || index > self.rows[table as usize] {
+            return Err(Error::InvalidData);
+        }
+        let mut pos = start + (index as usize - 1) * row_size;
+        let mut values = Vec::with_capacity(SCHEMA[table as usize].1.len());
+        for &col in SCHEMA[table as usize].1 {
+            let width = self.width(col);
+            let raw = if width == 2 { read_u16(self.stream, pos)? as u32 } else { read_u32(self.stream, pos)? };
+            pos += width;
+            values.push(match col {
+                Col::Coded(bits, tables) => {
+                    let tag = (raw & ((1 << bits) - 1)) as usize;
+                    match tables.get(tag) {
+                        Some(&id) if id != NONE => (id as u32) << 24 | raw >> bits,
+                        _ => return Err(Error::InvalidData),
+                    }
+                }
+                _ => raw,
+            });
+        }
+        Ok(values)
+    }
+
+    /// Every row of a table; empty if it is absent.
+    fn table_rows(&self, table: u8) -> Result<Vec<Vec<u32>>> {
+        (1..=self.rows[table as usize]).map(|index| self.row(table, index)).collect()
+    }
+
+    fn string(&self, index: u32) -> Result<String> {
+        let tail = self.strings.get(index as usize..).ok_or(Error::InvalidData)?;
+        let len = tail.iter().position(|&b| b == 0).unwrap_or(tail.len());
+        Ok(String::from_utf8_lossy(&tail[..len]).into_owned())
+    }
+
+    fn guid(&self, index: u32) -> Result<Option<String>> {
+        if index == 0 {
+            return Ok(None);
+        }
+        let g = read_bytes(self.guids, (index as usize - 1) * 16, 16)?;
+        Ok(Some(format!(
+            "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{}",
+            read_u32(g, 0)?,
+            read_u16(g, 4)?,
+            read_u16(g, 6)?,
+            g[8],
+            g[9],
+            g[10..].iter().map(|b| format!("{b:02X}")).collect::<String>(),
+        )))
+    }
+
+    /// Blob contents, after the compressed length prefix (ECMA-335 II.24.2.4).
+    fn blob(&self, index: u32) -> Result<&'a [u8]> {
+        let at = index as usize;
+        let first = read_u8(self.blobs, at)?;
+        let (len, header) = match first {
+            b if b & 0x80 == 0 => (b as usize, 1),
+            b if b & 0xC0 == 0x80 => (((b & 0x3F) as usize) << 8 | read_u8(self.blobs, at + 1)? as usize, 2),
+            b if b & 0xE0 == 0xC0 => {
+                let bytes = read_bytes(self.blobs, at, 4)?;
+                (u32::from_be_bytes([b & 0x1F, bytes[1], bytes[2], bytes[3]]) as usize, 4)
+            }
+            _ => return Err(Error::InvalidData),
+        };
+        read_bytes(self.blobs, at + header, len)
+    }
+
+    fn blob_hex(&self, index: u32) -> Result<String> {
+        Ok(self.blob(index)?.iter().map(|b| format!("{b:02X}")).collect())
+    }
+
+    /// `Namespace.Name` of a `TypeDef` or `TypeRef` token.
+    fn type_name(&self, token: u32) -> Option<String> {
+        let (namespace, name) = match (token >> 24) as u8 {
+            TYPE_DEF => {
+                let row = self.row(TYPE_DEF, token & 0x00FF_FFFF).ok()?;
+                (row[2], row[1])
+            }
+            TYPE_REF => {
+                let row = self.row(TYPE_REF, token & 0x00FF_FFFF).ok()?;
+                (row[2], row[1])
+            }
+            _ => return None,
+        };
+        let namespace = self.string(namespace).ok()?;
+        let name = self.string(name).ok()?;
+        Some(if namespace.is_empty() { name } else { format!("{namespace}.{name}") })
+    }
+
+    /// Full name of the type owning row `index` of the field or method list,
+    /// found from the `TypeDef` list ranges (column 4 or 5). The list starts
+    /// are ascending, so the owner is the last type starting at or before it.
+    fn owner(&self, type_defs: &[Vec<u32>], column: usize, index: u32) -> Option<String> {
+        let owner = type_defs.partition_point(|row| row[column] <= index).checked_sub(1)?;
+        if type_defs[owner][column] == 0 {
+            return None;
+        }
+        self.type_name((TYPE_DEF as u32) << 24 | (owner as u32 + 1))
+    }
+
+    fn decode(&self) -> Result<MetadataTables> {
+        let version = |row: &[u32]| format!("{}.{}.{}.{}", row[0], row[1], row[2], row[3]);
+
+        let type_def_rows = self.table_rows(TYPE_DEF)?;
+        let method_rows = self.table_rows(METHOD_DEF)?;
+
+        let module = match self.table_rows(MODULE)?.first() {
+            Some(row) => Some(ModuleDef {
+                generation: row[0] as u16,
+                name: self.string(row[1])?,
+                mvid: self.guid(row[2])?,
+            }),
+            None => None,
+        };
+
+        let type_refs = self
+            .table_rows(TYPE_REF)?
+            .iter()
+            .map(|row| {
+                Ok(TypeRef {
+                    resolution_scope: row[0],
+                    namespace: self.string(row[2])?,
+                    name: self.string(row[1])?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let type_defs = type_def_rows
+            .iter()
+            .map(|row| {
+                Ok(TypeDef {
+                    flags: row[0],
+                    namespace: self.string(row[2])?,
+                    name: self.string(row[1])?,
+                    extends: self.type_name(row[3]),
+                    field_list: row[4],
+                    method_list: row[5],
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let fields = self
+            .table_rows(FIELD)?
+            .iter()
+            .enumerate()
+            .map(|(index, row)| {
+                Ok(FieldDef {
+                    flags: row[0] as u16,
+                    name: self.string(row[1])?,
+                    declaring_type: self.owner(&type_def_rows, 4, index as u32 + 1),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let methods = method_rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| {
+                Ok(MethodDef {
+                    rva: row[0],
+                    impl_flags: row[1] as u16,
+                    flags: row[2] as u16,
+                    name: self.string(row[3])?,
+                    declaring_type: self.owner(&type_def_rows, 5, index as u32 + 1),
+                    param_list: row[5],
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let params = self
+            .table_rows(PARAM)?
+            .iter()
+            .map(|row| {
+                Ok(ParamDef {
+                    flags: row[0] as u16,
+                    sequence: row[1] as u16,
+                    name: self.string(row[2])?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let member_ref_rows = self.table_rows(MEMBER_REF)?;
+        let member_refs = member_ref_rows
+            .iter()
+            .map(|row| {
+                Ok(MemberRef {
+                    parent: row[0],
+                    parent_name: self.type_name(row[0]),
+                    name: self.string(row[1])?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let custom_attributes = self
+            .table_rows(CUSTOM_ATTRIBUTE)?
+            .iter()
+            .map(|row| {
+                let constructor = row[1];
+                let index = (constructor & 0x00FF_FFFF) as usize;
+                let attribute_type = match (constructor >> 24) as u8 {
+                    MEMBER_REF => member_ref_rows.get(index.wrapping_sub(1)).and_then(|r| self.type_name(r[0])),
+                    _ => self.owner(&type_def_rows, 5, index as u32),
+                };
+                Ok(CustomAttribute {
+                    parent: row[0],
+                    constructor,
+                    attribute_type,
+                    value_size: self.blob(row[2]).map_or(0, <[u8]>::len),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let module_refs: Vec<String> = self
+            .table_rows(MODULE_REF)?
+            .iter()
+            .map(|row| self.string(row[0]))
+            .collect::<Result<_>>()?;
+
+        let impl_maps = self
+            .table_rows(IMPL_MAP)?
+            .iter()
+            .map(|row| {
+                let member_index = row[1] & 0x00FF_FFFF;
+                let member = match (row[1] >> 24) as u8 {
+                    METHOD_DEF => self.row(METHOD_DEF, member_index).and_then(|r| self.string(r[3]))?,
+                    _ => self.row(FIELD, member_index).and_then(|r| self.string(r[1]))?,
+                };
+                Ok(ImplMap {
+                    mapping_flags: row[0] as u16,
+                    member,
+                    import_name: self.string(row[2])?,
+                    module: module_refs.get((row[3] as usize).wrapping_sub(1)).cloned().unwrap_or_default(),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let assembly = match self.table_rows(ASSEMBLY)?.first() {
+            Some(row) => Some(AssemblyDef {
+                hash_alg_id: row[0],
+                version: version(&row[1..5]),
+                flags: row[5],
+                public_key: self.blob_hex(row[6])?,
+                name: self.string(row[7])?,
+                culture: self.string(row[8])?,
+            }),
+            None => None,
+        };
+
+        let assembly_refs = self
+            .table_rows(ASSEMBLY_REF)?
+            .iter()
+            .map(|row| {
+                Ok(AssemblyRef {
+                    version: version(&row[0..4]),
+                    flags: row[4],
+                    public_key_or_token: self.blob_hex(row[5])?,
+                    name: self.string(row[6])?,
+                    culture: self.string(row[7])?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let manifest_resources = self
+            .table_rows(MANIFEST_RESOURCE)?
+            .iter()
+            .map(|row| {
+                Ok(ManifestResource {
+                    offset: row[0],
+                    flags: row[1],
+                    name: self.string(row[2])?,
+                    implementation: (row[3] & 0x00FF_FFFF != 0).then_some(row[3]),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(MetadataTables {
+            major_version: self.major_version,
+            minor_version: self.minor_version,
+            heap_sizes: self.heap_sizes,
+            tables: (0..TABLE_COUNT)
+                .filter(|&id| self.layout[id].is_some())
+                .map(|id| TableInfo { id: id as u8, name: SCHEMA[id].0, rows: self.rows[id] })
+                .collect(),
+            module,
+            type_refs,
+            type_defs,
+            fields,
+            methods,
+            params,
+            member_refs,
+            custom_attributes,
+            module_refs,
+            impl_maps,
+            assembly,
+            assembly_refs,
+            manifest_resources,
+        })
+    }
+}
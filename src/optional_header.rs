@@ -1,33 +1,57 @@
 use crate::headers::{OptionalHeader32, OptionalHeader64, OptionalHeader, FileHeader, DataDirectory};
-use std::ptr;
 use crate::errors::{Error, Result};
-use crate::utils::read_u16;
+use crate::utils::{read_u16, read_bytes, ByteReader};
 
-// Read the PE Optional Header (32- or 64-bit) right after the COFF FileHeader.
+/// Size of the PE32 optional header up to (not including) the data directories.
+const HEADER32_FIXED_SIZE: usize = 96;
+/// Size of the PE32+ optional header up to (not including) the data directories.
+const HEADER64_FIXED_SIZE: usize = 112;
+
+impl OptionalHeader {
+    /// Read the PE Optional Header (32- or 64-bit) right after the COFF FileHeader.
     ///
     /// This looks at the 2-byte “magic” at
     /// `e_lfanew + 4 + size_of::<FileHeader>()`:
     /// - 0x10B → PE32 (`OptionalHeader32`)
     /// - 0x20B → PE32+ (`OptionalHeader64`)
-impl OptionalHeader {
+    ///
+    /// Only the first `number_of_rva_and_sizes` data directories (at most 16)
+    /// are read; the rest are left zeroed.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidSize`] if the header runs past the end of `buffer`.
+    /// - [`Error::UnsupportedOptionalHeader`] for any other magic value.
     pub fn parse_optional_header(buffer: &[u8], e_lfanew: usize) -> Result<OptionalHeader> {
-        let fh_offset = e_lfanew + 4;
-        let oh_offset: usize = fh_offset + std::mem::size_of::<FileHeader>();
-        
-        let magic = read_u16(&buffer, oh_offset)?;
-        // Unsafe read: caller must ensure
-        // 1. `buffer.len() >= e_lfanew + 4 + size_of::<FileHeader>() + size_of::<OptionalHeaderXX>()`  
-        // 2. `buffer` really contains a valid PE image so offsets match up.
-        let optional_header: OptionalHeader = match magic {
-            0x10B => OptionalHeader::Header32(unsafe {
-                ptr::read_unaligned(buffer.as_ptr().add(oh_offset) as *const OptionalHeader32)
-            }),
-            0x20B => OptionalHeader::Header64(unsafe {
-                ptr::read_unaligned(buffer.as_ptr().add(oh_offset) as *const OptionalHeader64)
-            }),
-            other => return Err(Error::UnsupportedOptionalHeader(other)),
-        };
-        Ok(optional_header)
+        let fh_offset = e_lfanew.saturating_add(4);
+        let oh_offset: usize = fh_offset.saturating_add(FileHeader::SIZE);
+
+        let magic = read_u16(buffer, oh_offset)?;
+        match magic {
+            0x10B => Self::parse_as(buffer, e_lfanew, false),
+            0x20B => Self::parse_as(buffer, e_lfanew, true),
+            other => Err(Error::UnsupportedOptionalHeader(other)),
+        }
+    }
+
+    /// Decode the optional header as PE32 (`pe32_plus == false`) or PE32+,
+    /// whatever its magic says. Used by lenient parsing to recover from a bad magic.
+    pub(crate) fn parse_as(buffer: &[u8], e_lfanew: usize, pe32_plus: bool) -> Result<OptionalHeader> {
+        let oh_offset = e_lfanew.saturating_add(4 + FileHeader::SIZE);
+        if pe32_plus {
+            read_bytes(buffer, oh_offset, HEADER64_FIXED_SIZE)?;
+            Ok(OptionalHeader::Header64(parse_header64(buffer, oh_offset)?))
+        } else {
+            read_bytes(buffer, oh_offset, HEADER32_FIXED_SIZE)?;
+            Ok(OptionalHeader::Header32(parse_header32(buffer, oh_offset)?))
+        }
+    }
+
+    /// Bytes this header occupies on disk: the fixed fields plus the declared
+    /// data directories (capped at 16).
+    pub fn expected_size(&self) -> usize {
+        let fixed = if self.is_64() { HEADER64_FIXED_SIZE } else { HEADER32_FIXED_SIZE };
+        fixed + self.number_of_rva_and_sizes().min(16) as usize * 8
     }
 
 
@@ -40,6 +64,12 @@ impl OptionalHeader {
         }
     }
 
+    /// `true` for PE32+ images, which use 64-bit addresses and thunks.
+    #[inline]
+    pub fn is_64(&self) -> bool {
+        matches!(self, OptionalHeader::Header64(_))
+    }
+
     /// Data directories (shared between variants)
     #[inline]
     pub fn data_directory(&self) -> &[DataDirectory; 16] {
@@ -50,3 +80,89 @@ impl OptionalHeader {
     }
 }
 
+
+fn parse_header32(buffer: &[u8], offset: usize) -> Result<OptionalHeader32> {
+    let mut r = ByteReader::new(buffer, offset);
+    let mut header = OptionalHeader32 {
+        magic:                      r.u16()?,
+        major_linker_version:       r.u8()?,
+        minor_linker_version:       r.u8()?,
+        size_of_code:               r.u32()?,
+        size_of_initialized_data:   r.u32()?,
+        size_of_uninitialized_data: r.u32()?,
+        address_of_entry_point:     r.u32()?,
+        base_of_code:               r.u32()?,
+        base_of_data:               r.u32()?,
+        image_base:                 r.u32()?,
+        section_alignment:          r.u32()?,
+        file_alignment:             r.u32()?,
+        major_os_version:           r.u16()?,
+        minor_os_version:           r.u16()?,
+        major_image_version:        r.u16()?,
+        minor_image_version:        r.u16()?,
+        major_subsystem_version:    r.u16()?,
+        minor_subsystem_version:    r.u16()?,
+        win32_version_value:        r.u32()?,
+        size_of_image:              r.u32()?,
+        size_of_headers:            r.u32()?,
+        checksum:                   r.u32()?,
+        subsystem:                  r.u16()?,
+        dll_characteristics:        r.u16()?,
+        size_of_stack_reserve:      r.u32()?,
+        size_of_stack_commit:       r.u32()?,
+        size_of_heap_reserve:       r.u32()?,
+        size_of_heap_commit:        r.u32()?,
+        loader_flags:               r.u32()?,
+        number_of_rva_and_sizes:    r.u32()?,
+        data_directory:             [DataDirectory { virtual_address: 0, size: 0 }; 16],
+    };
+    read_data_directories(&mut r, header.number_of_rva_and_sizes, &mut header.data_directory)?;
+    Ok(header)
+}
+
+fn parse_header64(buffer: &[u8], offset: usize) -> Result<OptionalHeader64> {
+    let mut r = ByteReader::new(buffer, offset);
+    let mut header = OptionalHeader64 {
+        magic:                      r.u16()?,
+        major_linker_version:       r.u8()?,
+        minor_linker_version:       r.u8()?,
+        size_of_code:               r.u32()?,
+        size_of_initialized_data:   r.u32()?,
+        size_of_uninitialized_data: r.u32()?,
+        address_of_entry_point:     r.u32()?,
+        base_of_code:               r.u32()?,
+        image_base:                 r.u64()?,
+        section_alignment:          r.u32()?,
+        file_alignment:             r.u32()?,
+        major_os_version:           r.u16()?,
+        minor_os_version:           r.u16()?,
+        major_image_version:        r.u16()?,
+        minor_image_version:        r.u16()?,
+        major_subsystem_version:    r.u16()?,
+        minor_subsystem_version:    r.u16()?,
+        win32_version_value:        r.u32()?,
+        size_of_image:              r.u32()?,
+        size_of_headers:            r.u32()?,
+        checksum:                   r.u32()?,
+        subsystem:                  r.u16()?,
+        dll_characteristics:        r.u16()?,
+        size_of_stack_reserve:      r.u64()?,
+        size_of_stack_commit:       r.u64()?,
+        size_of_heap_reserve:       r.u64()?,
+        size_of_heap_commit:        r.u64()?,
+        loader_flags:               r.u32()?,
+        number_of_rva_and_sizes:    r.u32()?,
+        data_directory:             [DataDirectory { virtual_address: 0, size: 0 }; 16],
+    };
+    read_data_directories(&mut r, header.number_of_rva_and_sizes, &mut header.data_directory)?;
+    Ok(header)
+}
+
+/// Read the declared number of data directories (capped at 16).
+fn read_data_directories(r: &mut ByteReader, count: u32, out: &mut [DataDirectory; 16]) -> Result<()> {
+    for entry in out.iter_mut().take(count.min(16) as usize) {
+        entry.virtual_address = r.u32()?;
+        entry.size = r.u32()?;
+    }
+    Ok(())
+}
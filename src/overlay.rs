@@ -0,0 +1,145 @@
+use md5::Md5;
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use crate::entropy::shannon_entropy;
+use crate::parser::PeFile;
+use crate::utils::read_u32;
+
+/// How far into the overlay to look for installer markers.
+const MARKER_SCAN_LIMIT: usize = 0x10000;
+/// How far from the end of the overlay to look for the PyInstaller cookie.
+const COOKIE_SCAN_LIMIT: usize = 0x1000;
+const PYINSTALLER_COOKIE: &[u8] = b"MEI\x0C\x0B\x0A\x0B\x0E";
+
+/// Best-effort guess of what was appended to the image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum OverlayFormat {
+    Zip,
+    SevenZip,
+    Cab,
+    Nsis,
+    InnoSetup,
+    PyInstaller,
+    /// OLE compound file, as used by MSI packages.
+    Ole,
+    /// Another PE image.
+    Pe,
+}
+
+/// Data appended after the last section.
+#[derive(Debug, Clone, Serialize)]
+pub struct Overlay {
+    /// File offset of the first overlay byte.
+    pub offset: usize,
+    pub size: usize,
+    /// Shannon entropy in bits per byte.
+    pub entropy: f64,
+    /// Hashes of the overlay bytes, as lowercase hex.
+    pub md5: String,
+    pub sha1: String,
+    pub sha256: String,
+    pub format: Option<OverlayFormat>,
+}
+
+impl PeFile<'_> {
+    /// Locate the overlay: the data past the end of the furthest section's
+    /// raw data (or the headers, for files without sections).
+    ///
+    /// The attribute certificate table is not part of the overlay. When it
+    /// sits right at the end of the sections, as signing tools place it, the
+    /// overlay is whatever follows it; otherwise the overlay stops where the
+    /// table begins. Returns `None` when nothing is left.
+    pub fn overlay_range(&self) -> Option<(usize, usize)> {
+        let len = self.buffer.len();
+        let headers_end = (self.optional_header.size_of_headers() as usize).min(len);
+        let mut start = self
+            .sections
+            .iter()
+            .filter(|section| section.size_of_raw_data != 0)
+            .map(|section| self.section_raw_range(section).end)
+            .fold(headers_end, usize::max);
+        let mut end = len;
+
+        let security_dir = self.optional_header.data_directory()[4];
+        if security_dir.virtual_address != 0 && security_dir.size != 0 {
+            let cert_start = security_dir.virtual_address as usize;
+            let cert_end = cert_start.saturating_add(security_dir.size as usize).min(len);
+            if cert_start <= start && cert_end > start {
+                start = cert_end;
+            } else if cert_start > start && cert_start < end {
+                end = cert_start;
+            }
+        }
+
+        (start < end).then_some((start, end - start))
+    }
+
+    /// Borrow the overlay bytes, if any.
+    pub fn overlay_data(&self) -> Option<&[u8]> {
+        self.overlay_range().map(|(offset, size)| &self.buffer[offset..offset + size])
+    }
+
+    /// Describe the overlay: location, entropy, hashes and likely format.
+    pub fn overlay(&self) -> Option<Overlay> {
+        let (offset, size) = self.overlay_range()?;
+        let data = &self.buffer[offset..offset + size];
+        Some(Overlay {
+            offset,
+            size,
+            entropy: shannon_entropy(data),
+            md5: hex_digest::<Md5>(data),
+            sha1: hex_digest::<Sha1>(data),
+            sha256: hex_digest::<Sha256>(data),
+            format: identify(data),
+        })
+    }
+}
+
+/// Identify an overlay from its leading magic, installer markers near the
+/// start, or the PyInstaller cookie near the end.
+pub fn identify(data: &[u8]) -> Option<OverlayFormat> {
+    let magics: [(&[u8], OverlayFormat); 4] = [
+        (b"PK\x03\x04", OverlayFormat::Zip),
+        (b"7z\xBC\xAF\x27\x1C", OverlayFormat::SevenZip),
+        (b"MSCF", OverlayFormat::Cab),
+        (b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1", OverlayFormat::Ole),
+    ];
+    if let Some((_, format)) = magics.iter().find(|(magic, _)| data.starts_with(magic)) {
+        return Some(*format);
+    }
+    // NSIS's first header starts with a flags dword before its signature.
+    if data.get(4..20) == Some(b"\xEF\xBE\xAD\xDENullsoftInst") {
+        return Some(OverlayFormat::Nsis);
+    }
+    if is_pe(data) {
+        return Some(OverlayFormat::Pe);
+    }
+
+    let tail = &data[data.len().saturating_sub(COOKIE_SCAN_LIMIT)..];
+    if contains(tail, PYINSTALLER_COOKIE) {
+        return Some(OverlayFormat::PyInstaller);
+    }
+    let head = &data[..data.len().min(MARKER_SCAN_LIMIT)];
+    if head.starts_with(b"rDlPtS") || contains(head, b"Inno Setup Setup Data") {
+        return Some(OverlayFormat::InnoSetup);
+    }
+    None
+}
+
+fn is_pe(data: &[u8]) -> bool {
+    data.starts_with(b"MZ")
+        && read_u32(data, 0x3C)
+            .ok()
+            .and_then(|e_lfanew| data.get(e_lfanew as usize..e_lfanew as usize + 4))
+            == Some(b"PE\0\0")
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+fn hex_digest<D: Digest>(data: &[u8]) -> String {
+    D::digest(data).iter().map(|b| format!("{b:02x}")).collect()
+}
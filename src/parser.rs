@@ -1,16 +1,22 @@
+use std::io::Read;
 use std::path::Path;
-use crate::headers::{DosHeader, FileHeader, OptionalHeader, SectionHeader, PE_SIGNATURE};
+use crate::anomalies::{Anomaly, AnomalyKind, Severity};
+use crate::headers::{DosHeader, FileHeader, OptionalHeader, SectionHeader, DOS_MAGIC, PE_SIGNATURE};
+use crate::buffer::PeBuffer;
 use crate::view::Parsed;
 use serde::Serialize;
 use crate::errors::{Error, Result};
 use crate::utils::read_u32;
 
 /// Loaded PE file with its main headers and sections.
+///
+/// The lifetime ties the file to borrowed bytes when it was built with
+/// [`PeFile::from_bytes`]; files that own their data are `PeFile<'static>`.
 #[derive(Serialize)]
-pub struct PeFile {
-    /// Entire file contents.
+pub struct PeFile<'a> {
+    /// Entire file contents (owned, borrowed or memory-mapped).
     #[serde(skip_serializing)]
-    pub buffer: Vec<u8>,
+    pub buffer: PeBuffer<'a>,
 
     /// Offset to the NT header (e_lfanew).
     #[serde(skip_serializing)]
@@ -26,17 +32,12 @@ pub struct PeFile {
     pub sections: Vec<SectionHeader>,
 }
 
-impl PeFile {
-    /// Verify DOS header size and PE signature.
+impl<'a> PeFile<'a> {
+    /// Verify the PE signature at `e_lfanew`.
     ///
-    /// Returns an error if the file is too short or the PE signature is invalid.
-    fn validate(&self) -> Result<()> {
-        let buf = &self.buffer;
-        if buf.len() < 0x40 {
-            return Err(Error::InvalidSize { expected: 0x40, found: buf.len() });
-        }
-
-        let sig = read_u32(buf, self.e_lfanew)?;
+    /// Returns an error if the signature lies outside the buffer or isn't `PE\0\0`.
+    fn validate(buffer: &[u8], e_lfanew: usize) -> Result<()> {
+        let sig = read_u32(buffer, e_lfanew)?;
         if sig != PE_SIGNATURE {
             return Err(Error::InvalidPeSignature(sig));
         }
@@ -46,16 +47,149 @@ impl PeFile {
 
     /// Read a PE file from `path`, parse headers and sections, and validate signature.
     ///
+    /// This is a thin wrapper that reads the file and hands it to [`PeFile::from_vec`].
+    ///
     /// # Errors
     /// Returns an error if I/O fails, headers are malformed, or validation fails. If everything is great: return PE structure.
-    pub fn parse(path: &Path) -> Result<Self> {
-        let buffer         = std::fs::read(path)?;
+    pub fn parse(path: &Path) -> Result<PeFile<'static>> {
+        PeFile::from_vec(std::fs::read(path)?)
+    }
+
+    /// Parse a PE image that lives in a caller-owned slice, without copying it.
+    ///
+    /// Works the same over a memory map, a network capture or a sample pulled
+    /// out of an archive; use [`PeFile::into_owned`] to detach it later.
+    ///
+    /// # Errors
+    /// Same as [`PeFile::from_vec`].
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self> {
+        Self::from_buffer(PeBuffer::Borrowed(bytes))
+    }
+
+    /// Read a PE image from any source (file, cursor, socket buffer...).
+    ///
+    /// Reading starts at the reader's current position and runs to the end, so
+    /// an image embedded in an archive or capture can be parsed in place.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if reading fails, otherwise the same errors as [`PeFile::from_vec`].
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<PeFile<'static>> {
+        let mut buffer = Vec::new();
+        reader.read_to_end(&mut buffer)?;
+        PeFile::from_vec(buffer)
+    }
+
+    /// Parse a PE image that is already in memory, taking ownership of the buffer.
+    ///
+    /// # Errors
+    /// Returns an error if headers are malformed or the PE signature is invalid.
+    pub fn from_vec(buffer: Vec<u8>) -> Result<PeFile<'static>> {
+        PeFile::from_buffer(PeBuffer::Owned(buffer))
+    }
+
+    /// Memory-map the file at `path` and parse it in place.
+    ///
+    /// Nothing is copied to the heap, which keeps bulk scans of large
+    /// installers cheap. The mapping is read-only; as with any memory map, the
+    /// file must not be truncated or modified while the `PeFile` is alive.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the file can't be opened or mapped, otherwise
+    /// the same errors as [`PeFile::from_vec`].
+    #[cfg(feature = "mmap")]
+    pub fn from_mmap(path: &Path) -> Result<PeFile<'static>> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: the map is read-only and owned by the returned `PeFile`; the
+        // caller is responsible for not mutating the file underneath it.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        PeFile::from_buffer(PeBuffer::Mapped(map))
+    }
+
+    /// Parse headers and sections out of `buffer`.
+    ///
+    /// Every header is read through bounds-checked reads, so truncated or hostile
+    /// images produce an [`Error`] rather than reading out of bounds.
+    pub fn from_buffer(buffer: PeBuffer<'a>) -> Result<Self> {
         let dos_header     = DosHeader::parse(&buffer)?;
         let e_lfanew       = dos_header.e_lfanew();
+        Self::validate(&buffer, e_lfanew)?;
         let file_header    = FileHeader::parse_file_header(&buffer, e_lfanew)?;
         let optional_header= OptionalHeader::parse_optional_header(&buffer, e_lfanew)?;
         let sections       = SectionHeader::parse_section_headers(&buffer, &file_header, e_lfanew)?;
 
+        Ok(PeFile {
+            buffer,
+            e_lfanew,
+            file_header,
+            optional_header,
+            sections,
+        })
+    }
+
+    /// Parse as much of `buffer` as possible, recording problems instead of aborting.
+    ///
+    /// A bad DOS magic, a bad PE signature, an unknown optional-header magic or
+    /// a truncated section table are reported as [`Anomaly`] entries and parsing
+    /// carries on; the result also includes everything [`PeFile::anomalies`] finds.
+    ///
+    /// # Errors
+    /// Only fails when a header needed to build a `PeFile` can't be read at all
+    /// (e.g. the buffer ends before the COFF or optional header).
+    pub fn from_buffer_lenient(buffer: PeBuffer<'a>) -> Result<(Self, Vec<Anomaly>)> {
+        let mut anomalies = Vec::new();
+
+        let dos_header = DosHeader::read(&buffer)?;
+        if dos_header.e_magic != DOS_MAGIC {
+            anomalies.push(Anomaly::new(
+                Severity::Error,
+                AnomalyKind::BadDosMagic,
+                Some(0),
+                format!("DOS magic is 0x{:04X}, expected 0x{DOS_MAGIC:04X}", dos_header.e_magic),
+            ));
+        }
+
+        let e_lfanew = dos_header.e_lfanew();
+        if let Err(err) = Self::validate(&buffer, e_lfanew) {
+            if let Error::InvalidSize { .. } = err {
+                return Err(err);
+            }
+            anomalies.push(Anomaly::new(Severity::Error, AnomalyKind::BadPeSignature, Some(e_lfanew), err.to_string()));
+        }
+
+        let file_header = FileHeader::parse_file_header(&buffer, e_lfanew)?;
+        let optional_header = match OptionalHeader::parse_optional_header(&buffer, e_lfanew) {
+            Err(Error::UnsupportedOptionalHeader(magic)) => {
+                // Guess the layout from the machine and the declared header size.
+                let pe32_plus = matches!(file_header.machine, 0x8664 | 0xAA64)
+                    || file_header.size_of_optional_header >= 240;
+                anomalies.push(Anomaly::new(
+                    Severity::Error,
+                    AnomalyKind::UnsupportedOptionalHeader,
+                    Some(e_lfanew + 4 + FileHeader::SIZE),
+                    format!("optional header magic 0x{magic:04X}, reading it as {}", if pe32_plus { "PE32+" } else { "PE32" }),
+                ));
+                OptionalHeader::parse_as(&buffer, e_lfanew, pe32_plus)?
+            }
+            other => other?,
+        };
+
+        let sections = match SectionHeader::parse_section_headers(&buffer, &file_header, e_lfanew) {
+            Ok(sections) => sections,
+            Err(Error::InvalidSize { found, .. }) => {
+                let table = e_lfanew + 4 + FileHeader::SIZE + file_header.size_of_optional_header as usize;
+                let fits = found.saturating_sub(table) / SectionHeader::SIZE;
+                anomalies.push(Anomaly::new(
+                    Severity::Error,
+                    AnomalyKind::TruncatedSectionTable,
+                    Some(table),
+                    format!("{} sections declared, only {fits} fit in the file", file_header.number_of_sections),
+                ));
+                let available = FileHeader { number_of_sections: fits as u16, ..file_header };
+                SectionHeader::parse_section_headers(&buffer, &available, e_lfanew)?
+            }
+            Err(err) => return Err(err),
+        };
+
         let pe = PeFile {
             buffer,
             e_lfanew,
@@ -63,9 +197,29 @@ impl PeFile {
             optional_header,
             sections,
         };
+        anomalies.extend(pe.anomalies());
+        Ok((pe, anomalies))
+    }
+
+    /// Lenient counterpart of [`PeFile::from_bytes`]; see [`PeFile::from_buffer_lenient`].
+    pub fn from_bytes_lenient(bytes: &'a [u8]) -> Result<(Self, Vec<Anomaly>)> {
+        Self::from_buffer_lenient(PeBuffer::Borrowed(bytes))
+    }
 
-        pe.validate()?;
-        Ok(pe)
+    /// Lenient counterpart of [`PeFile::parse`]; see [`PeFile::from_buffer_lenient`].
+    pub fn parse_lenient(path: &Path) -> Result<(PeFile<'static>, Vec<Anomaly>)> {
+        PeFile::from_buffer_lenient(PeBuffer::Owned(std::fs::read(path)?))
+    }
+
+    /// Detach this file from borrowed bytes by copying them if needed.
+    pub fn into_owned(self) -> PeFile<'static> {
+        PeFile {
+            buffer: self.buffer.into_owned(),
+            e_lfanew: self.e_lfanew,
+            file_header: self.file_header,
+            optional_header: self.optional_header,
+            sections: self.sections,
+        }
     }
 
     /// Return a reference to this `PeFile`.
@@ -0,0 +1,421 @@
+use serde::Serialize;
+
+use crate::der::{self, context, context_primitive, Tlv};
+use crate::der::{TAG_INTEGER, TAG_OCTET_STRING, TAG_OID, TAG_SEQUENCE, TAG_SET};
+use crate::errors::{Error, Result};
+
+const OID_SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+const OID_SPC_INDIRECT_DATA: &str = "1.3.6.1.4.1.311.2.1.4";
+const OID_TST_INFO: &str = "1.2.840.113549.1.9.16.1.4";
+const OID_MESSAGE_DIGEST: &str = "1.2.840.113549.1.9.4";
+const OID_SIGNING_TIME: &str = "1.2.840.113549.1.9.5";
+const OID_COUNTER_SIGNATURE: &str = "1.2.840.113549.1.9.6";
+const OID_SPC_SP_OPUS_INFO: &str = "1.3.6.1.4.1.311.2.1.12";
+const OID_MS_TIMESTAMP: &str = "1.3.6.1.4.1.311.3.3.1";
+const OID_NESTED_SIGNATURE: &str = "1.3.6.1.4.1.311.2.4.1";
+
+/// How deeply nested signatures and timestamps are followed.
+const MAX_NESTING: usize = 8;
+
+/// An X.509 certificate from the signature's certificate bag.
+#[derive(Debug, Clone, Serialize)]
+pub struct Certificate {
+    /// X.509 version (1, 2 or 3).
+    pub version: u64,
+    /// Serial number as uppercase hex.
+    pub serial_number: String,
+    /// Subject DN, e.g. `C=US, O=Example, CN=Example Signer`.
+    pub subject: String,
+    /// Issuer DN in the same format as `subject`.
+    pub issuer: String,
+    /// `notBefore` as `YYYY-MM-DD HH:MM:SS` UTC.
+    pub not_before: String,
+    /// `notAfter` as `YYYY-MM-DD HH:MM:SS` UTC.
+    pub not_after: String,
+    pub signature_algorithm: String,
+}
+
+/// The `SpcIndirectDataContent` digest: the Authenticode hash of the image.
+#[derive(Debug, Clone, Serialize)]
+pub struct IndirectData {
+    /// Type of the signed data, normally `SPC_PE_IMAGE_DATA`.
+    pub data_type: String,
+    pub digest_algorithm: String,
+    /// Digest as uppercase hex.
+    pub digest: String,
+}
+
+/// Decoded RFC 3161 `TSTInfo`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimestampInfo {
+    pub version: u64,
+    pub policy: String,
+    pub hash_algorithm: String,
+    /// Hash of the timestamped signature, as uppercase hex.
+    pub hashed_message: String,
+    pub serial_number: String,
+    /// `genTime` as `YYYY-MM-DD HH:MM:SS` UTC.
+    pub time: String,
+}
+
+/// A `SignerInfo` with its decoded authenticated and unauthenticated attributes.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignerInfo {
+    pub version: u64,
+    /// Issuer DN of the signing certificate.
+    pub issuer: Option<String>,
+    /// Serial number of the signing certificate.
+    pub serial_number: Option<String>,
+    /// Subject key identifier, when the signer is identified that way.
+    pub subject_key_identifier: Option<String>,
+    pub digest_algorithm: String,
+    pub signature_algorithm: String,
+    /// Program name from `SpcSpOpusInfo`.
+    pub program_name: Option<String>,
+    /// More-info URL from `SpcSpOpusInfo`.
+    pub more_info_url: Option<String>,
+    /// Signed `messageDigest` attribute, as uppercase hex.
+    pub message_digest: Option<String>,
+    /// Signed `signingTime` attribute (legacy countersignatures carry one).
+    pub signing_time: Option<String>,
+    /// PKCS#9 countersignatures (`1.2.840.113549.1.9.6`).
+    pub counter_signatures: Vec<SignerInfo>,
+    /// RFC 3161 timestamps (`1.3.6.1.4.1.311.3.3.1`).
+    pub timestamps: Vec<SignedData>,
+    /// Additional signatures appended by dual signing (`1.3.6.1.4.1.311.2.4.1`).
+    pub nested_signatures: Vec<SignedData>,
+}
+
+/// A PKCS#7 / CMS `SignedData` structure.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignedData {
+    pub version: u64,
+    pub digest_algorithms: Vec<String>,
+    pub content_type: String,
+    /// Authenticode image digest, for `SPC_INDIRECT_DATA` content.
+    pub indirect_data: Option<IndirectData>,
+    /// Timestamp token info, for RFC 3161 `TSTInfo` content.
+    pub timestamp_info: Option<TimestampInfo>,
+    pub certificates: Vec<Certificate>,
+    pub signers: Vec<SignerInfo>,
+}
+
+impl SignedData {
+    /// Find the certificate a signer was issued, by issuer and serial number.
+    pub fn signer_certificate(&self, signer: &SignerInfo) -> Option<&Certificate> {
+        self.certificates.iter().find(|certificate| {
+            Some(&certificate.issuer) == signer.issuer.as_ref()
+                && Some(&certificate.serial_number) == signer.serial_number.as_ref()
+        })
+    }
+}
+
+/// Decode a DER `ContentInfo` wrapping PKCS#7 `SignedData`.
+///
+/// This is pure parsing: no signatures or certificate chains are verified.
+///
+/// # Errors
+/// Returns `Error::InvalidData` if the DER is malformed or the content is not
+/// `SignedData`.
+pub fn parse_signed_data(data: &[u8]) -> Result<SignedData> {
+    parse_content_info(der::parse(data)?, 0)
+}
+
+fn parse_content_info(content_info: Tlv, depth: usize) -> Result<SignedData> {
+    if depth > MAX_NESTING {
+        return Err(Error::InvalidData);
+    }
+    let mut r = content_info.expect(TAG_SEQUENCE)?.reader();
+    if r.expect(TAG_OID)?.oid()? != OID_SIGNED_DATA {
+        return Err(Error::InvalidData);
+    }
+    let explicit = r.expect(context(0))?;
+    let mut r = explicit.reader().expect(TAG_SEQUENCE)?.reader();
+
+    let version = r.expect(TAG_INTEGER)?.integer_u64()?;
+    let digest_algorithms = r
+        .expect(TAG_SET)?
+        .reader()
+        .all()?
+        .iter()
+        .map(algorithm)
+        .collect::<Result<_>>()?;
+
+    let mut encap = r.expect(TAG_SEQUENCE)?.reader();
+    let content_type = encap.expect(TAG_OID)?.oid()?;
+    let content = match encap.optional(context(0))? {
+        Some(explicit) => {
+            let inner = explicit.reader().read()?;
+            // CMS wraps eContent in an OCTET STRING; PKCS#7 Authenticode doesn't.
+            Some(if inner.tag == TAG_OCTET_STRING { der::parse(inner.value)? } else { inner })
+        }
+        None => None,
+    };
+    let indirect_data = match (&content, content_type.as_str()) {
+        (Some(content), OID_SPC_INDIRECT_DATA) => Some(parse_indirect_data(content)?),
+        _ => None,
+    };
+    let timestamp_info = match (&content, content_type.as_str()) {
+        (Some(content), OID_TST_INFO) => Some(parse_tst_info(content)?),
+        _ => None,
+    };
+
+    let certificates = match r.optional(context(0))? {
+        // Attribute certificates and other choices carry other tags; skip them.
+        Some(bag) => bag
+            .reader()
+            .all()?
+            .into_iter()
+            .filter(|item| item.tag == TAG_SEQUENCE)
+            .map(|item| parse_certificate(&item))
+            .collect::<Result<_>>()?,
+        None => Vec::new(),
+    };
+    r.optional(context(1))?;
+
+    let signers = r
+        .expect(TAG_SET)?
+        .reader()
+        .all()?
+        .iter()
+        .map(|signer| parse_signer_info(signer, depth))
+        .collect::<Result<_>>()?;
+
+    Ok(SignedData {
+        version,
+        digest_algorithms,
+        content_type: oid_name(&content_type),
+        indirect_data,
+        timestamp_info,
+        certificates,
+        signers,
+    })
+}
+
+fn parse_indirect_data(content: &Tlv) -> Result<IndirectData> {
+    let mut r = content.expect(TAG_SEQUENCE)?.reader();
+    let data_type = r.expect(TAG_SEQUENCE)?.reader().expect(TAG_OID)?.oid()?;
+    let mut digest_info = r.expect(TAG_SEQUENCE)?.reader();
+    let digest_algorithm = algorithm(&digest_info.expect(TAG_SEQUENCE)?)?;
+    let digest = hex(digest_info.expect(TAG_OCTET_STRING)?.value);
+
+    Ok(IndirectData { data_type: oid_name(&data_type), digest_algorithm, digest })
+}
+
+fn parse_tst_info(content: &Tlv) -> Result<TimestampInfo> {
+    let mut r = content.expect(TAG_SEQUENCE)?.reader();
+    let version = r.expect(TAG_INTEGER)?.integer_u64()?;
+    let policy = r.expect(TAG_OID)?.oid()?;
+    let mut imprint = r.expect(TAG_SEQUENCE)?.reader();
+    let hash_algorithm = algorithm(&imprint.expect(TAG_SEQUENCE)?)?;
+    let hashed_message = hex(imprint.expect(TAG_OCTET_STRING)?.value);
+    let serial_number = r.expect(TAG_INTEGER)?.integer_hex()?;
+    let time = r.read()?.time()?;
+
+    Ok(TimestampInfo { version, policy, hash_algorithm, hashed_message, serial_number, time })
+}
+
+fn parse_certificate(certificate: &Tlv) -> Result<Certificate> {
+    let mut r = certificate.reader();
+    let mut tbs = r.expect(TAG_SEQUENCE)?.reader();
+    let signature_algorithm = algorithm(&r.expect(TAG_SEQUENCE)?)?;
+
+    let version = match tbs.optional(context(0))? {
+        Some(explicit) => explicit.reader().expect(TAG_INTEGER)?.integer_u64()? + 1,
+        None => 1,
+    };
+    let serial_number = tbs.expect(TAG_INTEGER)?.integer_hex()?;
+    tbs.expect(TAG_SEQUENCE)?;
+    let issuer = name(&tbs.expect(TAG_SEQUENCE)?)?;
+    let mut validity = tbs.expect(TAG_SEQUENCE)?.reader();
+    let not_before = validity.read()?.time()?;
+    let not_after = validity.read()?.time()?;
+    let subject = name(&tbs.expect(TAG_SEQUENCE)?)?;
+
+    Ok(Certificate { version, serial_number, subject, issuer, not_before, not_after, signature_algorithm })
+}
+
+fn parse_signer_info(signer: &Tlv, depth: usize) -> Result<SignerInfo> {
+    if depth > MAX_NESTING {
+        return Err(Error::InvalidData);
+    }
+    let mut r = signer.expect(TAG_SEQUENCE)?.reader();
+    let version = r.expect(TAG_INTEGER)?.integer_u64()?;
+
+    let sid = r.read()?;
+    let (issuer, serial_number, subject_key_identifier) = match sid.tag {
+        TAG_SEQUENCE => {
+            let mut sid = sid.reader();
+            let issuer = name(&sid.expect(TAG_SEQUENCE)?)?;
+            let serial = sid.expect(TAG_INTEGER)?.integer_hex()?;
+            (Some(issuer), Some(serial), None)
+        }
+        tag if tag == context_primitive(0) => (None, None, Some(hex(sid.value))),
+        _ => return Err(Error::InvalidData),
+    };
+    let digest_algorithm = algorithm(&r.expect(TAG_SEQUENCE)?)?;
+
+    let mut info = SignerInfo {
+        version,
+        issuer,
+        serial_number,
+        subject_key_identifier,
+        digest_algorithm,
+        signature_algorithm: String::new(),
+        program_name: None,
+        more_info_url: None,
+        message_digest: None,
+        signing_time: None,
+        counter_signatures: Vec::new(),
+        timestamps: Vec::new(),
+        nested_signatures: Vec::new(),
+    };
+
+    if let Some(signed) = r.optional(context(0))? {
+        for (oid, values) in attributes(&signed)? {
+            let Some(value) = values.first() else { continue };
+            match oid.as_str() {
+                OID_MESSAGE_DIGEST => info.message_digest = Some(hex(value.expect(TAG_OCTET_STRING)?.value)),
+                OID_SIGNING_TIME => info.signing_time = Some(value.time()?),
+                OID_SPC_SP_OPUS_INFO => {
+                    let (program_name, more_info_url) = parse_opus_info(value)?;
+                    info.program_name = program_name;
+                    info.more_info_url = more_info_url;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    info.signature_algorithm = algorithm(&r.expect(TAG_SEQUENCE)?)?;
+    r.expect(TAG_OCTET_STRING)?;
+
+    if let Some(unsigned) = r.optional(context(1))? {
+        for (oid, values) in attributes(&unsigned)? {
+            for value in values {
+                match oid.as_str() {
+                    OID_COUNTER_SIGNATURE => info.counter_signatures.push(parse_signer_info(&value, depth + 1)?),
+                    OID_MS_TIMESTAMP => info.timestamps.push(parse_content_info(value, depth + 1)?),
+                    OID_NESTED_SIGNATURE => info.nested_signatures.push(parse_content_info(value, depth + 1)?),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok(info)
+}
+
+/// `SpcSpOpusInfo`: optional program name and more-info link.
+fn parse_opus_info(value: &Tlv) -> Result<(Option<String>, Option<String>)> {
+    let mut r = value.expect(TAG_SEQUENCE)?.reader();
+    let program_name = match r.optional(context(0))? {
+        Some(explicit) => Some(spc_string(&explicit.reader().read()?)?),
+        None => None,
+    };
+    let more_info_url = match r.optional(context(1))? {
+        Some(explicit) => {
+            let link = explicit.reader().read()?;
+            match link.tag & 0x1F {
+                // url [0] IMPLICIT IA5String
+                0 => Some(String::from_utf8_lossy(link.value).into_owned()),
+                // file [2] EXPLICIT SpcString
+                2 => Some(spc_string(&link.reader().read()?)?),
+                _ => None,
+            }
+        }
+        None => None,
+    };
+    Ok((program_name, more_info_url))
+}
+
+/// `SpcString`: `[0]` BMPString or `[1]` IA5String, both implicit.
+fn spc_string(value: &Tlv) -> Result<String> {
+    match value.tag {
+        tag if tag == context_primitive(0) => Ok(der::Tlv { tag: der::TAG_BMP_STRING, ..*value }.string()?),
+        tag if tag == context_primitive(1) => Ok(String::from_utf8_lossy(value.value).into_owned()),
+        _ => Err(Error::InvalidData),
+    }
+}
+
+/// `SET OF Attribute` as `(type, values)` pairs.
+fn attributes<'a>(set: &Tlv<'a>) -> Result<Vec<(String, Vec<Tlv<'a>>)>> {
+    set.reader()
+        .all()?
+        .iter()
+        .map(|attribute| {
+            let mut r = attribute.expect(TAG_SEQUENCE)?.reader();
+            let oid = r.expect(TAG_OID)?.oid()?;
+            let values = r.expect(TAG_SET)?.reader().all()?;
+            Ok((oid, values))
+        })
+        .collect()
+}
+
+/// `AlgorithmIdentifier` reduced to its readable name.
+fn algorithm(identifier: &Tlv) -> Result<String> {
+    let oid = identifier.expect(TAG_SEQUENCE)?.reader().expect(TAG_OID)?.oid()?;
+    Ok(oid_name(&oid))
+}
+
+/// X.500 `Name` formatted as `TYPE=value` pairs in encoded order.
+fn name(name: &Tlv) -> Result<String> {
+    let mut parts = Vec::new();
+    for rdn in name.reader().all()? {
+        for pair in rdn.expect(TAG_SET)?.reader().all()? {
+            let mut r = pair.expect(TAG_SEQUENCE)?.reader();
+            let oid = r.expect(TAG_OID)?.oid()?;
+            let value = r.read()?;
+            let key = match oid.as_str() {
+                "2.5.4.3" => "CN",
+                "2.5.4.5" => "serialNumber",
+                "2.5.4.6" => "C",
+                "2.5.4.7" => "L",
+                "2.5.4.8" => "ST",
+                "2.5.4.9" => "STREET",
+                "2.5.4.10" => "O",
+                "2.5.4.11" => "OU",
+                "1.2.840.113549.1.9.1" => "emailAddress",
+                "0.9.2342.19200300.100.1.25" => "DC",
+                other => other,
+            };
+            let value = value.string().unwrap_or_else(|_| hex(value.value));
+            parts.push(format!("{key}={value}"));
+        }
+    }
+    Ok(parts.join(", "))
+}
+
+/// Readable name of a well-known OID, or the dotted OID itself.
+pub fn oid_name(oid: &str) -> String {
+    let name = match oid {
+        "1.2.840.113549.2.5" => "md5",
+        "1.3.14.3.2.26" => "sha1",
+        "2.16.840.1.101.3.4.2.1" => "sha256",
+        "2.16.840.1.101.3.4.2.2" => "sha384",
+        "2.16.840.1.101.3.4.2.3" => "sha512",
+        "1.2.840.113549.1.1.1" => "rsaEncryption",
+        "1.2.840.113549.1.1.4" => "md5WithRSAEncryption",
+        "1.2.840.113549.1.1.5" => "sha1WithRSAEncryption",
+        "1.2.840.113549.1.1.10" => "rsassaPss",
+        "1.2.840.113549.1.1.11" => "sha256WithRSAEncryption",
+        "1.2.840.113549.1.1.12" => "sha384WithRSAEncryption",
+        "1.2.840.113549.1.1.13" => "sha512WithRSAEncryption",
+        "1.2.840.10045.2.1" => "ecPublicKey",
+        "1.2.840.10045.4.3.2" => "ecdsa-with-SHA256",
+        "1.2.840.10045.4.3.3" => "ecdsa-with-SHA384",
+        "1.2.840.10045.4.3.4" => "ecdsa-with-SHA512",
+        "1.2.840.113549.1.7.1" => "data",
+        OID_SIGNED_DATA => "signedData",
+        OID_SPC_INDIRECT_DATA => "SPC_INDIRECT_DATA",
+        "1.3.6.1.4.1.311.2.1.15" => "SPC_PE_IMAGE_DATA",
+        "1.3.6.1.4.1.311.2.1.25" => "SPC_CAB_DATA",
+        OID_TST_INFO => "TSTInfo",
+        _ => return oid.to_string(),
+    };
+    name.to_string()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02X}")).collect()
+}
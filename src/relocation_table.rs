@@ -0,0 +1,239 @@
+use serde::Serialize;
+
+use crate::address::Mapping;
+use crate::errors::{Error, Result};
+use crate::parser::PeFile;
+use crate::utils::{read_u16, read_u32, read_u64, rva_to_offset};
+
+const IMAGE_REL_BASED_ABSOLUTE: u8 = 0;
+const IMAGE_REL_BASED_HIGH: u8 = 1;
+const IMAGE_REL_BASED_LOW: u8 = 2;
+const IMAGE_REL_BASED_HIGHLOW: u8 = 3;
+const IMAGE_REL_BASED_HIGHADJ: u8 = 4;
+const IMAGE_REL_BASED_ARM_MOV32: u8 = 5;
+const IMAGE_REL_BASED_THUMB_MOV32: u8 = 7;
+const IMAGE_REL_BASED_DIR64: u8 = 10;
+
+const MACHINE_ARM: u16 = 0x01C0;
+const MACHINE_ARMNT: u16 = 0x01C4;
+
+/// A single fixup inside a relocation block.
+#[derive(Debug, Clone, Serialize)]
+pub struct Relocation {
+    /// Raw relocation type (top 4 bits of the entry).
+    pub kind: u8,
+    /// Readable type name for this machine (e.g. `DIR64`, `THUMB_MOV32`).
+    pub type_name: &'static str,
+    /// Offset within the block's page (low 12 bits of the entry).
+    pub offset: u16,
+    /// RVA of the location to patch.
+    pub rva: u32,
+    /// Extra parameter carried by `HIGHADJ` in the following entry.
+    pub param: Option<u16>,
+}
+
+/// One `IMAGE_BASE_RELOCATION` block covering a 4 KiB page.
+#[derive(Debug, Clone, Serialize)]
+pub struct RelocationBlock {
+    pub page_rva: u32,
+    pub block_size: u32,
+    pub entries: Vec<Relocation>,
+}
+
+/// Readable name of a base relocation type; some values mean different things per machine.
+pub fn relocation_type_name(kind: u8, machine: u16) -> &'static str {
+    let arm = matches!(machine, MACHINE_ARM | MACHINE_ARMNT);
+    match kind {
+        IMAGE_REL_BASED_ABSOLUTE => "ABSOLUTE",
+        IMAGE_REL_BASED_HIGH => "HIGH",
+        IMAGE_REL_BASED_LOW => "LOW",
+        IMAGE_REL_BASED_HIGHLOW => "HIGHLOW",
+        IMAGE_REL_BASED_HIGHADJ => "HIGHADJ",
+        IMAGE_REL_BASED_ARM_MOV32 if arm => "ARM_MOV32",
+        5 => "MIPS_JMPADDR",
+        IMAGE_REL_BASED_THUMB_MOV32 if arm => "THUMB_MOV32",
+        7 => "RISCV_LOW12I",
+        8 => "RISCV_LOW12S",
+        9 => "MIPS_JMPADDR16",
+        IMAGE_REL_BASED_DIR64 => "DIR64",
+        _ => "UNKNOWN",
+    }
+}
+
+/// Parse the base relocation table (data directory 5).
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if the directory does not map into the
+/// file, or `Error::InvalidData` if a block declares a size smaller than its
+/// header, an odd size, or one that runs past the end of the directory.
+pub fn parse_relocation_table(pe: &PeFile) -> Result<Vec<RelocationBlock>> {
+    let reloc_dir = pe.optional_header.data_directory()[5];
+    if reloc_dir.virtual_address == 0 {
+        return Err(Error::InvalidTableOffset);
+    }
+    let start = rva_to_offset(pe, reloc_dir.virtual_address)
+        .ok_or(Error::InvalidTableOffset)?;
+    let end = start + reloc_dir.size as usize;
+    let machine = pe.file_header.machine;
+
+    let mut blocks = Vec::new();
+    let mut pos = start;
+    while pos + 8 <= end {
+        let page_rva   = read_u32(&pe.buffer, pos)?;
+        let block_size = read_u32(&pe.buffer, pos + 4)?;
+        if block_size == 0 && page_rva == 0 {
+            break;
+        }
+        if block_size < 8 || block_size & 1 != 0 || block_size as usize > end - pos {
+            return Err(Error::InvalidData);
+        }
+
+        let count = (block_size as usize - 8) / 2;
+        let mut entries = Vec::with_capacity(count);
+        let mut i = 0;
+        while i < count {
+            let entry  = read_u16(&pe.buffer, pos + 8 + i * 2)?;
+            let kind   = (entry >> 12) as u8;
+            let offset = entry & 0x0FFF;
+            i += 1;
+
+            // HIGHADJ stores the low half of the adjusted value in the next slot.
+            let param = if kind == IMAGE_REL_BASED_HIGHADJ && i < count {
+                i += 1;
+                Some(read_u16(&pe.buffer, pos + 8 + (i - 1) * 2)?)
+            } else {
+                None
+            };
+
+            entries.push(Relocation {
+                kind,
+                type_name: relocation_type_name(kind, machine),
+                offset,
+                rva: page_rva.wrapping_add(offset as u32),
+                param,
+            });
+        }
+
+        blocks.push(RelocationBlock { page_rva, block_size, entries });
+        pos += block_size as usize;
+    }
+
+    Ok(blocks)
+}
+
+/// Produce a copy of the file with every base relocation applied for `new_base`.
+///
+/// The result keeps the on-disk layout (it can be parsed again with
+/// [`PeFile::from_vec`]) and its `image_base` header field is set to
+/// `new_base`. Fixups that fall in zero-filled virtual space have no file
+/// bytes to patch and are skipped.
+///
+/// # Errors
+/// Returns the errors of [`parse_relocation_table`], `Error::InvalidTableOffset`
+/// if a fixup targets an unmapped RVA, or `Error::UnsupportedRelocation` for
+/// types other than `HIGH`, `LOW`, `HIGHLOW`, `HIGHADJ`, `DIR64`, `ARM_MOV32`
+/// and `THUMB_MOV32`.
+pub fn rebase(pe: &PeFile, new_base: u64) -> Result<Vec<u8>> {
+    let blocks = parse_relocation_table(pe)?;
+    let delta = new_base.wrapping_sub(pe.optional_header.image_base());
+    let arm = matches!(pe.file_header.machine, MACHINE_ARM | MACHINE_ARMNT);
+    let mut image = pe.buffer.to_vec();
+
+    for relocation in blocks.iter().flat_map(|block| &block.entries) {
+        if relocation.kind == IMAGE_REL_BASED_ABSOLUTE {
+            continue;
+        }
+        let at = match pe.map_rva(relocation.rva) {
+            Some(Mapping::File { offset, .. }) => offset,
+            Some(Mapping::ZeroFill { .. }) => continue,
+            None => return Err(Error::InvalidTableOffset),
+        };
+
+        match relocation.kind {
+            IMAGE_REL_BASED_HIGH => {
+                let value = read_u16(&image, at)?.wrapping_add((delta >> 16) as u16);
+                write(&mut image, at, &value.to_le_bytes())?;
+            }
+            IMAGE_REL_BASED_LOW => {
+                let value = read_u16(&image, at)?.wrapping_add(delta as u16);
+                write(&mut image, at, &value.to_le_bytes())?;
+            }
+            IMAGE_REL_BASED_HIGHLOW => {
+                let value = read_u32(&image, at)?.wrapping_add(delta as u32);
+                write(&mut image, at, &value.to_le_bytes())?;
+            }
+            IMAGE_REL_BASED_HIGHADJ => {
+                let low = relocation.param.unwrap_or(0) as i16 as i32 as u32;
+                let full = ((read_u16(&image, at)? as u32) << 16)
+                    .wrapping_add(low)
+                    .wrapping_add(delta as u32);
+                let high = (full.wrapping_add(0x8000) >> 16) as u16;
+                write(&mut image, at, &high.to_le_bytes())?;
+            }
+            IMAGE_REL_BASED_DIR64 => {
+                let value = read_u64(&image, at)?.wrapping_add(delta);
+                write(&mut image, at, &value.to_le_bytes())?;
+            }
+            IMAGE_REL_BASED_ARM_MOV32 if arm => {
+                let value = ((arm_imm16(read_u32(&image, at + 4)?) as u32) << 16 | arm_imm16(read_u32(&image, at)?) as u32)
+                    .wrapping_add(delta as u32);
+                let movw = set_arm_imm16(read_u32(&image, at)?, value as u16);
+                let movt = set_arm_imm16(read_u32(&image, at + 4)?, (value >> 16) as u16);
+                write(&mut image, at, &movw.to_le_bytes())?;
+                write(&mut image, at + 4, &movt.to_le_bytes())?;
+            }
+            IMAGE_REL_BASED_THUMB_MOV32 if arm => {
+                let movw = (read_u16(&image, at)?, read_u16(&image, at + 2)?);
+                let movt = (read_u16(&image, at + 4)?, read_u16(&image, at + 6)?);
+                let value = ((thumb_imm16(movt) as u32) << 16 | thumb_imm16(movw) as u32)
+                    .wrapping_add(delta as u32);
+                let (w1, w2) = set_thumb_imm16(movw, value as u16);
+                let (t1, t2) = set_thumb_imm16(movt, (value >> 16) as u16);
+                for (i, half) in [w1, w2, t1, t2].into_iter().enumerate() {
+                    write(&mut image, at + i * 2, &half.to_le_bytes())?;
+                }
+            }
+            other => return Err(Error::UnsupportedRelocation(other)),
+        }
+    }
+
+    // Record the new preferred base in the optional header.
+    let image_base_offset = pe.e_lfanew + 4 + 20 + if pe.optional_header.is_64() { 24 } else { 28 };
+    if pe.optional_header.is_64() {
+        write(&mut image, image_base_offset, &new_base.to_le_bytes())?;
+    } else {
+        write(&mut image, image_base_offset, &(new_base as u32).to_le_bytes())?;
+    }
+
+    Ok(image)
+}
+
+fn write(image: &mut [u8], offset: usize, bytes: &[u8]) -> Result<()> {
+    let found = image.len();
+    image
+        .get_mut(offset..offset + bytes.len())
+        .ok_or(Error::InvalidSize { expected: offset + bytes.len(), found })?
+        .copy_from_slice(bytes);
+    Ok(())
+}
+
+/// Immediate of an ARM-mode `MOVW`/`MOVT` (`imm4:imm12`).
+fn arm_imm16(ins: u32) -> u16 {
+    (((ins >> 16) & 0xF) << 12 | (ins & 0xFFF)) as u16
+}
+
+fn set_arm_imm16(ins: u32, imm: u16) -> u32 {
+    let imm = imm as u32;
+    (ins & !0x000F_0FFF) | ((imm >> 12) << 16) | (imm & 0xFFF)
+}
+
+/// Immediate of a Thumb-2 `MOVW`/`MOVT` pair of halfwords (`imm4:i:imm3:imm8`).
+fn thumb_imm16((hw1, hw2): (u16, u16)) -> u16 {
+    (hw1 & 0xF) << 12 | ((hw1 >> 10) & 1) << 11 | ((hw2 >> 12) & 7) << 8 | (hw2 & 0xFF)
+}
+
+fn set_thumb_imm16((hw1, hw2): (u16, u16), imm: u16) -> (u16, u16) {
+    let hw1 = (hw1 & !0x040F) | (imm >> 12) | ((imm >> 11) & 1) << 10;
+    let hw2 = (hw2 & !0x70FF) | ((imm >> 8) & 7) << 12 | (imm & 0xFF);
+    (hw1, hw2)
+}
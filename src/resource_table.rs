@@ -0,0 +1,215 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::errors::{Error, Result};
+use crate::parser::PeFile;
+use crate::utils::{read_bytes, read_rva_bytes, read_u16, rva_to_offset, ByteReader};
+
+/// Deepest directory nesting we follow. Real trees are 3 levels (type/name/language).
+const MAX_DEPTH: usize = 8;
+
+/// Name or numeric ID of a resource directory entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum ResourceId {
+    Name(String),
+    Id(u32),
+}
+
+/// Leaf of the resource tree (`IMAGE_RESOURCE_DATA_ENTRY`).
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceData {
+    /// RVA of the resource bytes (an RVA, unlike the other offsets in the tree).
+    pub rva: u32,
+    /// Size of the resource bytes.
+    pub size: u32,
+    /// Code page used to decode code point values within the resource.
+    pub code_page: u32,
+    pub reserved: u32,
+}
+
+/// A child of a resource directory: either another directory or a data leaf.
+#[derive(Debug, Serialize)]
+pub enum ResourceNode {
+    Directory(ResourceDirectory),
+    Data(ResourceData),
+}
+
+/// Named or ID entry of a resource directory.
+#[derive(Debug, Serialize)]
+pub struct ResourceEntry {
+    pub id: ResourceId,
+    pub node: ResourceNode,
+}
+
+/// One level of the resource tree (`IMAGE_RESOURCE_DIRECTORY`).
+#[derive(Debug, Serialize)]
+pub struct ResourceDirectory {
+    pub characteristics: u32,
+    pub time_date_stamp: u32,
+    pub major_version: u16,
+    pub minor_version: u16,
+    /// Named entries first, then ID entries, as stored in the file.
+    pub entries: Vec<ResourceEntry>,
+}
+
+/// A resource leaf together with the type/name/language path leading to it.
+#[derive(Debug, Serialize)]
+pub struct ResourceLeaf<'a> {
+    pub path: Vec<&'a ResourceId>,
+    pub data: &'a ResourceData,
+}
+
+impl ResourceData {
+    /// Borrow the raw resource bytes from the file.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidTableOffset` or `Error::InvalidSize` if the data
+    /// does not lie within the file.
+    pub fn bytes<'a>(&self, pe: &'a PeFile) -> Result<&'a [u8]> {
+        read_rva_bytes(pe, self.rva, self.size as usize)
+    }
+}
+
+impl ResourceDirectory {
+    /// Flatten the tree into every data leaf with its path from the root.
+    ///
+    /// For a regular tree the path is `[type, name, language]`.
+    pub fn leaves(&self) -> Vec<ResourceLeaf<'_>> {
+        let mut leaves = Vec::new();
+        let mut path = Vec::new();
+        collect_leaves(self, &mut path, &mut leaves);
+        leaves
+    }
+
+    /// All leaves under the given resource type (e.g. `16` for `RT_VERSION`).
+    pub fn leaves_of_type(&self, type_id: u32) -> Vec<ResourceLeaf<'_>> {
+        self.leaves()
+            .into_iter()
+            .filter(|leaf| leaf.path.first() == Some(&&ResourceId::Id(type_id)))
+            .collect()
+    }
+}
+
+fn collect_leaves<'a>(dir: &'a ResourceDirectory, path: &mut Vec<&'a ResourceId>, out: &mut Vec<ResourceLeaf<'a>>) {
+    for entry in &dir.entries {
+        path.push(&entry.id);
+        match &entry.node {
+            ResourceNode::Directory(sub) => collect_leaves(sub, path, out),
+            ResourceNode::Data(data) => out.push(ResourceLeaf { path: path.clone(), data }),
+        }
+        path.pop();
+    }
+}
+
+/// Well-known resource type name for a top-level ID (`RT_*` constants).
+pub fn resource_type_name(id: u32) -> Option<&'static str> {
+    Some(match id {
+        1 => "CURSOR",
+        2 => "BITMAP",
+        3 => "ICON",
+        4 => "MENU",
+        5 => "DIALOG",
+        6 => "STRING",
+        7 => "FONTDIR",
+        8 => "FONT",
+        9 => "ACCELERATOR",
+        10 => "RCDATA",
+        11 => "MESSAGETABLE",
+        12 => "GROUP_CURSOR",
+        14 => "GROUP_ICON",
+        16 => "VERSION",
+        17 => "DLGINCLUDE",
+        19 => "PLUGPLAY",
+        20 => "VXD",
+        21 => "ANICURSOR",
+        22 => "ANIICON",
+        23 => "HTML",
+        24 => "MANIFEST",
+        _ => return None,
+    })
+}
+
+/// Parse the resource directory tree (data directory 2).
+///
+/// Subdirectory and name offsets are relative to the start of the resource
+/// directory. Entries that point at a directory that was already parsed, or
+/// nest deeper than a sane limit, are skipped so cyclic or self-referencing
+/// trees still terminate.
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if the directory does not map into the
+/// file, or `Error::InvalidSize` if a directory header or entry is truncated.
+pub fn parse_resource_table(pe: &PeFile) -> Result<ResourceDirectory> {
+    let resource_dir = pe.optional_header.data_directory()[2];
+    if resource_dir.virtual_address == 0 {
+        return Err(Error::InvalidTableOffset);
+    }
+    let base = rva_to_offset(pe, resource_dir.virtual_address)
+        .ok_or(Error::InvalidTableOffset)?;
+
+    let mut visited = HashSet::new();
+    parse_directory(&pe.buffer, base, 0, 0, &mut visited)
+}
+
+fn parse_directory(buffer: &[u8], base: usize, offset: u32, depth: usize, visited: &mut HashSet<u32>) -> Result<ResourceDirectory> {
+    visited.insert(offset);
+
+    let start = base + offset as usize;
+    let mut r = ByteReader::new(buffer, start);
+    let characteristics = r.u32()?;
+    let time_date_stamp = r.u32()?;
+    let major_version   = r.u16()?;
+    let minor_version   = r.u16()?;
+    let named_entries   = r.u16()? as usize;
+    let id_entries      = r.u16()? as usize;
+    read_bytes(buffer, start + 16, (named_entries + id_entries) * 8)?;
+
+    let mut entries = Vec::with_capacity(named_entries + id_entries);
+    for _ in 0..named_entries + id_entries {
+        let name_or_id = r.u32()?;
+        let target     = r.u32()?;
+
+        let id = if name_or_id & 0x8000_0000 != 0 {
+            ResourceId::Name(read_name(buffer, base + (name_or_id & 0x7FFF_FFFF) as usize)?)
+        } else {
+            ResourceId::Id(name_or_id)
+        };
+
+        let node = if target & 0x8000_0000 != 0 {
+            let sub_offset = target & 0x7FFF_FFFF;
+            if depth + 1 >= MAX_DEPTH || visited.contains(&sub_offset) {
+                continue;
+            }
+            ResourceNode::Directory(parse_directory(buffer, base, sub_offset, depth + 1, visited)?)
+        } else {
+            let mut d = ByteReader::new(buffer, base + target as usize);
+            ResourceNode::Data(ResourceData {
+                rva:       d.u32()?,
+                size:      d.u32()?,
+                code_page: d.u32()?,
+                reserved:  d.u32()?,
+            })
+        };
+
+        entries.push(ResourceEntry { id, node });
+    }
+
+    Ok(ResourceDirectory {
+        characteristics,
+        time_date_stamp,
+        major_version,
+        minor_version,
+        entries,
+    })
+}
+
+/// Read a length-prefixed UTF-16 resource name (`IMAGE_RESOURCE_DIR_STRING_U`).
+fn read_name(buffer: &[u8], offset: usize) -> Result<String> {
+    let len = read_u16(buffer, offset)? as usize;
+    let units = (0..len)
+        .map(|i| read_u16(buffer, offset + 2 + i * 2))
+        .collect::<Result<Vec<u16>>>()?;
+    Ok(String::from_utf16_lossy(&units))
+}
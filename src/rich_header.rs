@@ -0,0 +1,221 @@
+use md5::{Digest, Md5};
+use serde::Serialize;
+
+use crate::errors::{Error, Result};
+use crate::parser::PeFile;
+use crate::utils::read_u32;
+
+/// `Rich`, the plaintext marker closing the header.
+const RICH_MARKER: u32 = 0x6863_6952;
+/// `DanS`, the first (masked) dword of the header.
+const DANS_MARKER: u32 = 0x536E_6144;
+
+/// One `@comp.id` record: how many objects a given tool build contributed.
+#[derive(Debug, Clone, Serialize)]
+pub struct RichEntry {
+    pub product_id: u16,
+    pub build: u16,
+    pub count: u32,
+    /// Product name from the linker's internal enumeration, e.g. `Utc1900_CPP`.
+    pub product: Option<&'static str>,
+    /// Kind of tool, e.g. "C/C++ compiler", "MASM" or "Linker".
+    pub tool: Option<&'static str>,
+    /// Visual Studio release the product id and build belong to.
+    pub visual_studio: Option<&'static str>,
+}
+
+/// The undocumented "Rich" header the Microsoft linker writes after the DOS stub.
+#[derive(Debug, Clone, Serialize)]
+pub struct RichHeader {
+    /// File offset of the `DanS` marker.
+    pub offset: usize,
+    /// Bytes from `DanS` up to and including the key after `Rich`.
+    pub size: usize,
+    /// XOR key, which doubles as the stored checksum.
+    pub key: u32,
+    /// Checksum recomputed from the DOS header, stub and entries.
+    pub checksum: u32,
+    /// Whether `checksum` equals `key`; a mismatch means the stub or the
+    /// entries were edited after linking.
+    pub valid: bool,
+    pub entries: Vec<RichEntry>,
+    /// MD5 of the unmasked header from `DanS` up to `Rich`, as lowercase hex
+    /// (the "Rich header hash" of pefile and VirusTotal).
+    pub hash: String,
+}
+
+/// Locate, unmask and validate the Rich header.
+///
+/// The header sits between the DOS stub and `e_lfanew`: a `DanS` marker and
+/// three zero dwords, then `(comp.id, count)` pairs, all XORed with the key
+/// that follows the plaintext `Rich` marker.
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if there is no Rich header, or
+/// `Error::InvalidData` if the `Rich` marker has no matching `DanS`.
+pub fn parse_rich_header(pe: &PeFile) -> Result<RichHeader> {
+    let end = pe.e_lfanew.min(pe.buffer.len());
+    let rich = (0x40..end.saturating_sub(7))
+        .step_by(4)
+        .find(|&pos| read_u32(&pe.buffer, pos).is_ok_and(|v| v == RICH_MARKER))
+        .ok_or(Error::InvalidTableOffset)?;
+    let key = read_u32(&pe.buffer, rich + 4)?;
+
+    let start = (0x40..rich)
+        .step_by(4)
+        .rev()
+        .find(|&pos| read_u32(&pe.buffer, pos).is_ok_and(|v| v ^ key == DANS_MARKER))
+        .ok_or(Error::InvalidData)?;
+
+    let clear: Vec<u32> = (start..rich)
+        .step_by(4)
+        .map(|pos| read_u32(&pe.buffer, pos).map(|v| v ^ key))
+        .collect::<Result<_>>()?;
+    // DanS is followed by three zero dwords before the first entry.
+    let entries: Vec<RichEntry> = clear
+        .get(4..)
+        .unwrap_or_default()
+        .chunks_exact(2)
+        .map(|pair| entry(pair[0], pair[1]))
+        .collect();
+
+    // Each byte of the DOS header and stub (minus e_lfanew), rotated by its
+    // offset, then each comp.id rotated by its count, on top of the offset.
+    let mut checksum = start as u32;
+    for (offset, &byte) in pe.buffer[..start].iter().enumerate() {
+        if (0x3C..0x40).contains(&offset) {
+            continue;
+        }
+        checksum = checksum.wrapping_add((byte as u32).rotate_left(offset as u32));
+    }
+    for entry in &entries {
+        let comp_id = (entry.product_id as u32) << 16 | entry.build as u32;
+        checksum = checksum.wrapping_add(comp_id.rotate_left(entry.count));
+    }
+
+    let bytes: Vec<u8> = clear.iter().flat_map(|v| v.to_le_bytes()).collect();
+    Ok(RichHeader {
+        offset: start,
+        size: rich + 8 - start,
+        key,
+        checksum,
+        valid: checksum == key,
+        entries,
+        hash: Md5::digest(&bytes).iter().map(|b| format!("{b:02x}")).collect(),
+    })
+}
+
+fn entry(comp_id: u32, count: u32) -> RichEntry {
+    let product_id = (comp_id >> 16) as u16;
+    let build = comp_id as u16;
+    let product = PRODUCT_NAMES.get(product_id as usize).copied();
+    RichEntry {
+        product_id,
+        build,
+        count,
+        product,
+        tool: product.and_then(tool_name),
+        visual_studio: visual_studio(product_id, build),
+    }
+}
+
+fn tool_name(product: &str) -> Option<&'static str> {
+    let tools = [
+        ("Utc", "C/C++ compiler"),
+        ("Phx", "C/C++ compiler (Phoenix)"),
+        ("Masm", "MASM"),
+        ("Linker", "Linker"),
+        ("Cvtres", "Resource converter"),
+        ("Cvtomf", "OMF converter"),
+        ("Cvtpgd", "Profile-guided optimizer"),
+        ("Export", "Export file"),
+        ("Implib", "Import library"),
+        ("Import0", "Imported functions"),
+        ("AliasObj", "Alias object"),
+        ("ILAsm", "IL assembler"),
+        ("VisualBasic", "Visual Basic"),
+        ("Resource", "Resource"),
+    ];
+
+    tools
+        .iter()
+        .find(|(prefix, _)| product.starts_with(prefix))
+        .map(|(_, name)| *name)
+}
+
+/// Visual Studio release, from the product id block and, for the ids shared
+/// by every release since 2015, the build number.
+fn visual_studio(product_id: u16, build: u16) -> Option<&'static str> {
+    Some(match product_id {
+        0x005A..=0x006C => "Visual Studio .NET 2003",
+        0x006D..=0x0082 => "Visual Studio 2005",
+        0x0083..=0x0097 => "Visual Studio 2008",
+        0x0098..=0x00B4 => "Visual Studio 2010",
+        0x00B5..=0x00C6 => "Visual Studio 2010 SP1",
+        0x00C7..=0x00D8 => "Visual Studio 2012",
+        0x00D9..=0x00EA => "Visual Studio 2013",
+        0x00EB..=0x00FC => "Visual Studio 2013 November CTP",
+        0x00FD..=0x010E => match build {
+            0..=24999 => "Visual Studio 2015",
+            25000..=27499 => "Visual Studio 2017",
+            27500..=30699 => "Visual Studio 2019",
+            _ => "Visual Studio 2022",
+        },
+        _ => return None,
+    })
+}
+
+/// Product names indexed by product id (the linker's `prodid` enumeration).
+const PRODUCT_NAMES: [&str; 0x10F] = [
+    "Unknown", "Import0", "Linker510", "Cvtomf510", "Linker600", "Cvtomf600", "Cvtres500",
+    "Utc11_Basic", "Utc11_C", "Utc12_Basic", "Utc12_C", "Utc12_CPP", "AliasObj60", "VisualBasic60",
+    "Masm613", "Masm710", "Linker511", "Cvtomf511", "Masm614", "Linker512", "Cvtomf512",
+    "Utc12_C_Std", "Utc12_CPP_Std", "Utc12_C_Book", "Utc12_CPP_Book", "Implib700", "Cvtomf700",
+    "Utc13_Basic", "Utc13_C", "Utc13_CPP", "Linker610", "Cvtomf610", "Linker601", "Cvtomf601",
+    "Utc12_1_Basic", "Utc12_1_C", "Utc12_1_CPP", "Linker620", "Cvtomf620", "AliasObj70",
+    "Linker621", "Cvtomf621", "Masm615", "Utc13_LTCG_C", "Utc13_LTCG_CPP", "Masm620", "ILAsm100",
+    "Utc12_2_Basic", "Utc12_2_C", "Utc12_2_CPP", "Utc12_2_C_Std", "Utc12_2_CPP_Std",
+    "Utc12_2_C_Book", "Utc12_2_CPP_Book", "Implib622", "Cvtomf622", "Cvtres501", "Utc13_C_Std",
+    "Utc13_CPP_Std", "Cvtpgd1300", "Linker622", "Linker700", "Export622", "Export700", "Masm700",
+    "Utc13_POGO_I_C", "Utc13_POGO_I_CPP", "Utc13_POGO_O_C", "Utc13_POGO_O_CPP", "Cvtres700",
+    "Cvtres710p", "Linker710p", "Cvtomf710p", "Export710p", "Implib710p", "Masm710p", "Utc1310p_C",
+    "Utc1310p_CPP", "Utc1310p_C_Std", "Utc1310p_CPP_Std", "Utc1310p_LTCG_C", "Utc1310p_LTCG_CPP",
+    "Utc1310p_POGO_I_C", "Utc1310p_POGO_I_CPP", "Utc1310p_POGO_O_C", "Utc1310p_POGO_O_CPP",
+    "Linker624", "Cvtomf624", "Export624", "Implib624", "Linker710", "Cvtomf710", "Export710",
+    "Implib710", "Cvtres710", "Utc1310_C", "Utc1310_CPP", "Utc1310_C_Std", "Utc1310_CPP_Std",
+    "Utc1310_LTCG_C", "Utc1310_LTCG_CPP", "Utc1310_POGO_I_C", "Utc1310_POGO_I_CPP",
+    "Utc1310_POGO_O_C", "Utc1310_POGO_O_CPP", "AliasObj710", "AliasObj710p", "Cvtpgd1310",
+    "Cvtpgd1310p", "Utc1400_C", "Utc1400_CPP", "Utc1400_C_Std", "Utc1400_CPP_Std",
+    "Utc1400_LTCG_C", "Utc1400_LTCG_CPP", "Utc1400_POGO_I_C", "Utc1400_POGO_I_CPP",
+    "Utc1400_POGO_O_C", "Utc1400_POGO_O_CPP", "Cvtpgd1400", "Linker800", "Cvtomf800", "Export800",
+    "Implib800", "Cvtres800", "Masm800", "AliasObj800", "PhoenixPrerelease", "Utc1400_CVTCIL_C",
+    "Utc1400_CVTCIL_CPP", "Utc1400_LTCG_MSIL", "Utc1500_C", "Utc1500_CPP", "Utc1500_C_Std",
+    "Utc1500_CPP_Std", "Utc1500_CVTCIL_C", "Utc1500_CVTCIL_CPP", "Utc1500_LTCG_C",
+    "Utc1500_LTCG_CPP", "Utc1500_LTCG_MSIL", "Utc1500_POGO_I_C", "Utc1500_POGO_I_CPP",
+    "Utc1500_POGO_O_C", "Utc1500_POGO_O_CPP", "Cvtpgd1500", "Linker900", "Export900", "Implib900",
+    "Cvtres900", "Masm900", "AliasObj900", "Resource", "AliasObj1000", "Cvtpgd1600", "Cvtres1000",
+    "Export1000", "Implib1000", "Linker1000", "Masm1000", "Phx1600_C", "Phx1600_CPP",
+    "Phx1600_CVTCIL_C", "Phx1600_CVTCIL_CPP", "Phx1600_LTCG_C", "Phx1600_LTCG_CPP",
+    "Phx1600_LTCG_MSIL", "Phx1600_POGO_I_C", "Phx1600_POGO_I_CPP", "Phx1600_POGO_O_C",
+    "Phx1600_POGO_O_CPP", "Utc1600_C", "Utc1600_CPP", "Utc1600_CVTCIL_C", "Utc1600_CVTCIL_CPP",
+    "Utc1600_LTCG_C", "Utc1600_LTCG_CPP", "Utc1600_LTCG_MSIL", "Utc1600_POGO_I_C",
+    "Utc1600_POGO_I_CPP", "Utc1600_POGO_O_C", "Utc1600_POGO_O_CPP", "AliasObj1010", "Cvtpgd1610",
+    "Cvtres1010", "Export1010", "Implib1010", "Linker1010", "Masm1010", "Utc1610_C", "Utc1610_CPP",
+    "Utc1610_CVTCIL_C", "Utc1610_CVTCIL_CPP", "Utc1610_LTCG_C", "Utc1610_LTCG_CPP",
+    "Utc1610_LTCG_MSIL", "Utc1610_POGO_I_C", "Utc1610_POGO_I_CPP", "Utc1610_POGO_O_C",
+    "Utc1610_POGO_O_CPP", "AliasObj1100", "Cvtpgd1700", "Cvtres1100", "Export1100", "Implib1100",
+    "Linker1100", "Masm1100", "Utc1700_C", "Utc1700_CPP", "Utc1700_CVTCIL_C", "Utc1700_CVTCIL_CPP",
+    "Utc1700_LTCG_C", "Utc1700_LTCG_CPP", "Utc1700_LTCG_MSIL", "Utc1700_POGO_I_C",
+    "Utc1700_POGO_I_CPP", "Utc1700_POGO_O_C", "Utc1700_POGO_O_CPP", "AliasObj1200", "Cvtpgd1800",
+    "Cvtres1200", "Export1200", "Implib1200", "Linker1200", "Masm1200", "Utc1800_C", "Utc1800_CPP",
+    "Utc1800_CVTCIL_C", "Utc1800_CVTCIL_CPP", "Utc1800_LTCG_C", "Utc1800_LTCG_CPP",
+    "Utc1800_LTCG_MSIL", "Utc1800_POGO_I_C", "Utc1800_POGO_I_CPP", "Utc1800_POGO_O_C",
+    "Utc1800_POGO_O_CPP", "AliasObj1210", "Cvtpgd1810", "Cvtres1210", "Export1210", "Implib1210",
+    "Linker1210", "Masm1210", "Utc1810_C", "Utc1810_CPP", "Utc1810_CVTCIL_C", "Utc1810_CVTCIL_CPP",
+    "Utc1810_LTCG_C", "Utc1810_LTCG_CPP", "Utc1810_LTCG_MSIL", "Utc1810_POGO_I_C",
+    "Utc1810_POGO_I_CPP", "Utc1810_POGO_O_C", "Utc1810_POGO_O_CPP", "AliasObj1400", "Cvtpgd1900",
+    "Cvtres1400", "Export1400", "Implib1400", "Linker1400", "Masm1400", "Utc1900_C", "Utc1900_CPP",
+    "Utc1900_CVTCIL_C", "Utc1900_CVTCIL_CPP", "Utc1900_LTCG_C", "Utc1900_LTCG_CPP",
+    "Utc1900_LTCG_MSIL", "Utc1900_POGO_I_C", "Utc1900_POGO_I_CPP", "Utc1900_POGO_O_C",
+    "Utc1900_POGO_O_CPP",
+];
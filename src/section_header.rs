@@ -1,7 +1,10 @@
 use crate::headers::{FileHeader, SectionHeader};
-use std::ptr;
 use crate::errors::Result;
+use crate::utils::{read_bytes, ByteReader};
 impl SectionHeader{
+    /// Size of one `IMAGE_SECTION_HEADER` on disk.
+    pub const SIZE: usize = 40;
+
      /// Parse all section headers declared in the COFF FileHeader.
     ///
     /// * `buffer` – complete PE image in memory.
@@ -11,29 +14,49 @@ impl SectionHeader{
     ///
     /// Calculates the start of the section table as:
     /// `e_lfanew + 4 + size_of::<FileHeader>() + file_header.size_of_optional_header as usize`.
-    /// Then allocates a Vec and reads each `SectionHeader` via `ptr::read_unaligned`.
+    /// The whole table is bounds-checked up front, then each `SectionHeader`
+    /// is decoded field by field.
     ///
-    /// # Safety
+    /// # Errors
     ///
-    /// Uses unaligned, direct memory reads. Caller must ensure:
-    /// - `buffer.len() >= section_offset + number_of_sections * size_of::<SectionHeader>()`
-    /// - The bytes in that range are valid `SectionHeader` entries.
+    /// Returns [`crate::Error::InvalidSize`] if the table declared by
+    /// `number_of_sections` doesn't fit in `buffer`.
     pub fn parse_section_headers(buffer: &[u8], file_header: &FileHeader, e_lfanew: usize, ) -> Result<Vec<SectionHeader>>{
         //section_offset = e_lfanew + PE_signature... 
-        let section_offset = e_lfanew + 4 + size_of::<crate::headers::FileHeader>() + file_header.size_of_optional_header as usize;
+        let section_offset = e_lfanew
+            .saturating_add(4 + FileHeader::SIZE)
+            .saturating_add(file_header.size_of_optional_header as usize);
         let number_of_sections = file_header.number_of_sections as usize;
+
+        // reject the whole table before allocating for a hostile count
+        read_bytes(buffer, section_offset, number_of_sections * Self::SIZE)?;
         let mut sections = Vec::with_capacity(number_of_sections);
-    
+
         // first section_header stars here
-        let base_ptr = unsafe { buffer.as_ptr().add(section_offset) as *const SectionHeader};
-    
-        for i in 0..number_of_sections{
-            let ptr_section = unsafe {base_ptr.add(i)};
-    
-            let section = unsafe {ptr::read_unaligned(ptr_section)};
-    
+        let mut r = ByteReader::new(buffer, section_offset);
+        for _ in 0..number_of_sections{
+            let section = SectionHeader {
+                name:                   r.bytes()?,
+                virtual_size:           r.u32()?,
+                virtual_address:        r.u32()?,
+                size_of_raw_data:       r.u32()?,
+                pointer_to_raw_data:    r.u32()?,
+                pointer_to_relocations: r.u32()?,
+                pointer_to_linenumbers: r.u32()?,
+                number_of_relocations:  r.u16()?,
+                number_of_linenumbers:  r.u16()?,
+                characteristics:        r.u32()?,
+            };
+
             sections.push(section);
         }
         Ok(sections)
     }
+
+    /// Section name with trailing NUL padding removed.
+    pub fn name_string(&self) -> String {
+        String::from_utf8_lossy(&self.name)
+            .trim_end_matches('\0')
+            .to_string()
+    }
 }
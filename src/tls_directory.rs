@@ -0,0 +1,106 @@
+use serde::Serialize;
+
+use crate::errors::{Error, Result};
+use crate::parser::PeFile;
+use crate::utils::{read_u32, read_u64, rva_to_offset, ByteReader};
+
+/// Upper bound on callbacks we enumerate, in case the array is never terminated.
+const MAX_CALLBACKS: usize = 4096;
+
+/// A TLS callback, run by the loader before the entry point.
+#[derive(Debug, Clone, Serialize)]
+pub struct TlsCallback {
+    /// Virtual address as stored in the callback array.
+    pub va: u64,
+    /// RVA of the callback, if the VA lies above `image_base`.
+    pub rva: Option<u32>,
+    /// Name of the section containing the callback.
+    pub section: Option<String>,
+}
+
+/// Decoded `IMAGE_TLS_DIRECTORY32` / `IMAGE_TLS_DIRECTORY64`.
+///
+/// The address fields are virtual addresses; their `*_rva` counterparts are
+/// converted using the preferred `image_base`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TlsDirectory {
+    pub start_address_of_raw_data: u64,
+    pub end_address_of_raw_data: u64,
+    pub address_of_index: u64,
+    pub address_of_callbacks: u64,
+    pub size_of_zero_fill: u32,
+    pub characteristics: u32,
+    pub raw_data_rva: Option<u32>,
+    pub raw_data_end_rva: Option<u32>,
+    pub index_rva: Option<u32>,
+    pub callbacks_rva: Option<u32>,
+    /// Callbacks in the order the loader calls them.
+    pub callbacks: Vec<TlsCallback>,
+}
+
+/// Parse the TLS directory (data directory 9) and enumerate its callbacks.
+///
+/// The structure width follows the optional header: 32-bit fields for PE32
+/// and 64-bit address fields for PE32+. The callback array is a
+/// null-terminated list of pointer-sized VAs.
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if the directory is absent or does not
+/// map into the file, or `Error::InvalidSize` if it is truncated.
+pub fn parse_tls_directory(pe: &PeFile) -> Result<TlsDirectory> {
+    let tls_dir = pe.optional_header.data_directory()[9];
+    if tls_dir.virtual_address == 0 {
+        return Err(Error::InvalidTableOffset);
+    }
+    let offset = rva_to_offset(pe, tls_dir.virtual_address)
+        .ok_or(Error::InvalidTableOffset)?;
+
+    let is_64 = pe.optional_header.is_64();
+    let mut r = ByteReader::new(&pe.buffer, offset);
+    let mut address = || if is_64 { r.u64() } else { r.u32().map(u64::from) };
+    let start_address_of_raw_data = address()?;
+    let end_address_of_raw_data   = address()?;
+    let address_of_index          = address()?;
+    let address_of_callbacks      = address()?;
+    let size_of_zero_fill         = r.u32()?;
+    let characteristics           = r.u32()?;
+
+    let callbacks_rva = non_null_rva(pe, address_of_callbacks);
+    let mut callbacks = Vec::new();
+    if let Some(array) = callbacks_rva.and_then(|rva| rva_to_offset(pe, rva)) {
+        let width = if is_64 { 8 } else { 4 };
+        for index in 0..MAX_CALLBACKS {
+            let at = array + index * width;
+            let va = if is_64 { read_u64(&pe.buffer, at)? } else { read_u32(&pe.buffer, at)? as u64 };
+            if va == 0 {
+                break;
+            }
+            let rva = pe.va_to_rva(va);
+            callbacks.push(TlsCallback {
+                va,
+                rva,
+                section: rva
+                    .and_then(|rva| pe.section_for_rva(rva))
+                    .map(|section| section.name_string()),
+            });
+        }
+    }
+
+    Ok(TlsDirectory {
+        start_address_of_raw_data,
+        end_address_of_raw_data,
+        address_of_index,
+        address_of_callbacks,
+        size_of_zero_fill,
+        characteristics,
+        raw_data_rva: non_null_rva(pe, start_address_of_raw_data),
+        raw_data_end_rva: non_null_rva(pe, end_address_of_raw_data),
+        index_rva: non_null_rva(pe, address_of_index),
+        callbacks_rva,
+        callbacks,
+    })
+}
+
+fn non_null_rva(pe: &PeFile, va: u64) -> Option<u32> {
+    if va == 0 { None } else { pe.va_to_rva(va) }
+}
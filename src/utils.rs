@@ -4,22 +4,20 @@ use crate::errors::{Error, Result};
 
 
 
+/// Convert an RVA to a file offset; shorthand for [`PeFile::rva_to_offset`].
 pub fn rva_to_offset(pe: &PeFile, rva: u32) -> Option<usize> {
-    for section in &pe.sections{
-        //check if rva is in a section
-        if rva > section.virtual_address && rva < section.virtual_address + section.virtual_size{
-            //difference e.g 0x2100 - 0x2000 it gives how many bytes we should move on in memory
-            let memory_delta = rva - section.virtual_address;
-            // section_start + how many bytes we should move
-            return Some(section.pointer_to_raw_data as usize + memory_delta as usize);
-        }
-    }
-    None
+    pe.rva_to_offset(rva)
+}
+
+/// Borrow `len` bytes of file data starting at `rva`.
+pub fn read_rva_bytes<'a>(pe: &'a PeFile, rva: u32, len: usize) -> Result<&'a [u8]> {
+    let offset = rva_to_offset(pe, rva).ok_or(Error::InvalidTableOffset)?;
+    read_bytes(&pe.buffer, offset, len)
 }
 
 pub fn read_dll_names(pe: &PeFile, rva: u32) -> Result<String>{
     //change RVA to offset in file
-    let offset = rva_to_offset(&pe, rva).ok_or(Error::InvalidTableOffset)?;
+    let offset = rva_to_offset(pe, rva).ok_or(Error::InvalidTableOffset)?;
     //we take from beginning to end, because we don't know where ('0x00') is.
     let buffer = &pe.buffer[offset..];
     //search for first ('0x00') that means it is end of dll_name
@@ -29,25 +27,115 @@ pub fn read_dll_names(pe: &PeFile, rva: u32) -> Result<String>{
 
     Ok(dll_name)
 }
-pub fn read_u32(block: &[u8], offset: usize) -> Result<u32> {
-    let expected_size = 4;
+
+/// Read a null-terminated ASCII string (e.g. an imported function name) at `rva`.
+///
+/// Bytes that are not valid UTF-8 are replaced rather than rejected, since
+/// mangled or obfuscated names are common in the wild.
+pub fn read_c_string(pe: &PeFile, rva: u32) -> Result<String> {
+    let offset = rva_to_offset(pe, rva).ok_or(Error::InvalidTableOffset)?;
+    let buffer = pe.buffer.get(offset..).ok_or(Error::InvalidTableOffset)?;
+    let len = buffer.iter().position(|&byte| byte == 0).unwrap_or(buffer.len());
+
+    Ok(String::from_utf8_lossy(&buffer[..len]).into_owned())
+}
+
+/// Borrow `len` bytes at `offset`, failing with the minimum buffer size that
+/// would have been needed instead of panicking or overflowing.
+pub fn read_bytes(block: &[u8], offset: usize, len: usize) -> Result<&[u8]> {
+    let end = offset.checked_add(len).ok_or(Error::InvalidSize { expected: usize::MAX, found: block.len() })?;
     block
-        .get(offset..offset + expected_size)
-        .ok_or(Error::InvalidSize { expected: expected_size, found: block.len() })?  // Zainicjalizowanie struktury
+        .get(offset..end)
+        .ok_or(Error::InvalidSize { expected: end, found: block.len() })
+}
+
+pub fn read_u64(block: &[u8], offset: usize) -> Result<u64> {
+    read_bytes(block, offset, 8)?
+        .try_into()
+        .map(u64::from_le_bytes)
+        .map_err(|_| Error::InvalidData)
+}
+
+pub fn read_u32(block: &[u8], offset: usize) -> Result<u32> {
+    read_bytes(block, offset, 4)?
         .try_into()
         .map(u32::from_le_bytes)
         .map_err(|_| Error::InvalidData)
 }
 
 pub fn read_u16(block: &[u8], offset: usize) -> Result<u16> {
-    let expected_size = 2;
-    block
-        .get(offset..offset + expected_size)
-        .ok_or(Error::InvalidSize {expected: expected_size, found: block.len() })?
+    read_bytes(block, offset, 2)?
         .try_into()
         .map(u16::from_le_bytes)
         .map_err(|_| Error::InvalidData)
 }
+
+pub fn read_u8(block: &[u8], offset: usize) -> Result<u8> {
+    Ok(read_bytes(block, offset, 1)?[0])
+}
+
+/// Bounds-checked little-endian cursor used to decode fixed-layout structures.
+///
+/// Every read advances the cursor and fails with [`Error::InvalidSize`] when
+/// the structure runs past the end of the buffer.
+pub struct ByteReader<'a> {
+    block: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    /// Start reading `block` at `offset`.
+    pub fn new(block: &'a [u8], offset: usize) -> Self {
+        ByteReader { block, pos: offset }
+    }
+
+    /// Current absolute position in the buffer.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn u8(&mut self) -> Result<u8> {
+        let value = read_u8(self.block, self.pos)?;
+        self.pos += 1;
+        Ok(value)
+    }
+
+    pub fn u16(&mut self) -> Result<u16> {
+        let value = read_u16(self.block, self.pos)?;
+        self.pos += 2;
+        Ok(value)
+    }
+
+    pub fn u32(&mut self) -> Result<u32> {
+        let value = read_u32(self.block, self.pos)?;
+        self.pos += 4;
+        Ok(value)
+    }
+
+    pub fn u64(&mut self) -> Result<u64> {
+        let value = read_u64(self.block, self.pos)?;
+        self.pos += 8;
+        Ok(value)
+    }
+
+    /// Read `N` raw bytes.
+    pub fn bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(read_bytes(self.block, self.pos, N)?);
+        self.pos += N;
+        Ok(out)
+    }
+
+    /// Read `N` consecutive little-endian `u16` values.
+    pub fn u16_array<const N: usize>(&mut self) -> Result<[u16; N]> {
+        let mut out = [0u16; N];
+        for value in out.iter_mut() {
+            *value = self.u16()?;
+        }
+        Ok(out)
+    }
+}
+
 pub fn format_as_hex(value: u32) -> String {
     format!("0x{:08X}", value)
 }
\ No newline at end of file
@@ -0,0 +1,310 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::errors::{Error, Result};
+use crate::parser::PeFile;
+use crate::resource_table::parse_resource_table;
+use crate::utils::{read_bytes, read_u16, read_u32};
+
+/// `RT_VERSION` resource type ID.
+const RT_VERSION: u32 = 16;
+/// Signature of `VS_FIXEDFILEINFO`.
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xFEEF_04BD;
+
+/// Decoded `VS_FIXEDFILEINFO`.
+#[derive(Debug, Clone, Serialize)]
+pub struct FixedFileInfo {
+    pub struct_version: u32,
+    /// File version as `[major, minor, build, revision]`.
+    pub file_version: [u16; 4],
+    /// Product version as `[major, minor, build, revision]`.
+    pub product_version: [u16; 4],
+    pub file_flags_mask: u32,
+    pub file_flags: u32,
+    /// Names of the flags set in `file_flags & file_flags_mask`.
+    pub flags: Vec<&'static str>,
+    pub file_os: u32,
+    /// Readable form of `file_os`.
+    pub os: &'static str,
+    pub file_type: u32,
+    /// Readable form of `file_type`.
+    pub kind: &'static str,
+    pub file_subtype: u32,
+    pub file_date: u64,
+}
+
+/// One `StringTable` block: strings for a single language/code page pair.
+#[derive(Debug, Clone, Serialize)]
+pub struct StringTable {
+    /// Language ID (e.g. `0x0409` for en-US).
+    pub language: u16,
+    /// Code page (e.g. `0x04B0` for Unicode).
+    pub code_page: u16,
+    /// Key/value pairs such as `CompanyName` or `FileVersion`.
+    pub strings: BTreeMap<String, String>,
+}
+
+/// A language/code page pair listed in `VarFileInfo\Translation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Translation {
+    pub language: u16,
+    pub code_page: u16,
+}
+
+/// Decoded `VS_VERSIONINFO` resource.
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionInfo {
+    pub fixed: Option<FixedFileInfo>,
+    pub string_tables: Vec<StringTable>,
+    pub translations: Vec<Translation>,
+}
+
+impl FixedFileInfo {
+    /// File version formatted as `major.minor.build.revision`.
+    pub fn file_version_string(&self) -> String {
+        format_version(self.file_version)
+    }
+
+    /// Product version formatted as `major.minor.build.revision`.
+    pub fn product_version_string(&self) -> String {
+        format_version(self.product_version)
+    }
+}
+
+impl VersionInfo {
+    /// Look up a string (e.g. `"ProductName"`) in the first table that has it.
+    pub fn string(&self, key: &str) -> Option<&str> {
+        self.string_tables
+            .iter()
+            .find_map(|table| table.strings.get(key))
+            .map(String::as_str)
+    }
+
+    pub fn company_name(&self) -> Option<&str> {
+        self.string("CompanyName")
+    }
+
+    pub fn product_name(&self) -> Option<&str> {
+        self.string("ProductName")
+    }
+
+    pub fn file_version(&self) -> Option<&str> {
+        self.string("FileVersion")
+    }
+
+    pub fn product_version(&self) -> Option<&str> {
+        self.string("ProductVersion")
+    }
+
+    pub fn file_description(&self) -> Option<&str> {
+        self.string("FileDescription")
+    }
+
+    pub fn original_filename(&self) -> Option<&str> {
+        self.string("OriginalFilename")
+    }
+
+    /// Decode a raw `VS_VERSIONINFO` blob.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidData` if the root block isn't `VS_VERSION_INFO`
+    /// or `Error::InvalidSize` if a block runs past the end of `data`.
+    pub fn parse(data: &[u8]) -> Result<VersionInfo> {
+        let root = Block::read(data, 0)?;
+        if root.key != "VS_VERSION_INFO" {
+            return Err(Error::InvalidData);
+        }
+
+        let fixed = if root.value.len() >= 52 && read_u32(root.value, 0)? == FIXED_FILE_INFO_SIGNATURE {
+            Some(parse_fixed(root.value)?)
+        } else {
+            None
+        };
+
+        let mut info = VersionInfo { fixed, string_tables: Vec::new(), translations: Vec::new() };
+        for child in root.children()? {
+            match child.key.as_str() {
+                "StringFileInfo" => {
+                    for table in child.children()? {
+                        info.string_tables.push(parse_string_table(&table)?);
+                    }
+                }
+                "VarFileInfo" => {
+                    for var in child.children()? {
+                        if var.key != "Translation" {
+                            continue;
+                        }
+                        for pair in var.value.chunks_exact(4) {
+                            info.translations.push(Translation {
+                                language: read_u16(pair, 0)?,
+                                code_page: read_u16(pair, 2)?,
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(info)
+    }
+}
+
+/// Find the `RT_VERSION` resource and decode it.
+///
+/// # Errors
+/// Returns `Error::InvalidTableOffset` if the file has no resources or no
+/// version resource, otherwise the errors of [`VersionInfo::parse`].
+pub fn parse_version_info(pe: &PeFile) -> Result<VersionInfo> {
+    let resources = parse_resource_table(pe)?;
+    let leaf = resources
+        .leaves_of_type(RT_VERSION)
+        .into_iter()
+        .next()
+        .ok_or(Error::InvalidTableOffset)?;
+    VersionInfo::parse(leaf.data.bytes(pe)?)
+}
+
+/// Generic version-resource block: `wLength`, `wValueLength`, `wType`, `szKey`,
+/// padding, `Value`, padding, children.
+struct Block<'a> {
+    key: String,
+    value: &'a [u8],
+    children: &'a [u8],
+}
+
+impl<'a> Block<'a> {
+    fn read(data: &'a [u8], offset: usize) -> Result<Self> {
+        let length       = read_u16(data, offset)? as usize;
+        let value_length = read_u16(data, offset + 2)? as usize;
+        let kind         = read_u16(data, offset + 4)?;
+        if length < 6 {
+            return Err(Error::InvalidData);
+        }
+        let block = read_bytes(data, offset, length)?;
+
+        let mut units = Vec::new();
+        let mut pos = 6;
+        loop {
+            let unit = read_u16(block, pos)?;
+            pos += 2;
+            if unit == 0 {
+                break;
+            }
+            units.push(unit);
+        }
+        let key = String::from_utf16_lossy(&units);
+
+        // Text values (wType == 1) are counted in UTF-16 code units.
+        let value_start = align4(pos).min(block.len());
+        let value_bytes = if kind == 1 { value_length * 2 } else { value_length };
+        let value_end = (value_start + value_bytes).min(block.len());
+        let children_start = align4(value_end).min(block.len());
+
+        Ok(Block {
+            key,
+            value: &block[value_start..value_end],
+            children: &block[children_start..],
+        })
+    }
+
+    fn children(&self) -> Result<Vec<Block<'a>>> {
+        let mut children = Vec::new();
+        let mut offset = 0;
+        while offset + 6 <= self.children.len() {
+            let length = read_u16(self.children, offset)? as usize;
+            if length == 0 {
+                break;
+            }
+            children.push(Block::read(self.children, offset)?);
+            offset = align4(offset + length);
+        }
+        Ok(children)
+    }
+
+    /// Value decoded as a null-terminated UTF-16 string.
+    fn text(&self) -> String {
+        let units: Vec<u16> = self.value
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .take_while(|&unit| unit != 0)
+            .collect();
+        String::from_utf16_lossy(&units)
+    }
+}
+
+fn parse_string_table(table: &Block) -> Result<StringTable> {
+    // The key is eight hex digits: language then code page, e.g. "040904B0".
+    let lang_cp = u32::from_str_radix(&table.key, 16).map_err(|_| Error::InvalidData)?;
+    let strings = table
+        .children()?
+        .into_iter()
+        .map(|string| (string.key.clone(), string.text()))
+        .collect();
+
+    Ok(StringTable {
+        language: (lang_cp >> 16) as u16,
+        code_page: lang_cp as u16,
+        strings,
+    })
+}
+
+fn parse_fixed(value: &[u8]) -> Result<FixedFileInfo> {
+    let split = |ms: u32, ls: u32| [(ms >> 16) as u16, ms as u16, (ls >> 16) as u16, ls as u16];
+    let file_flags_mask = read_u32(value, 24)?;
+    let file_flags      = read_u32(value, 28)?;
+    let file_os         = read_u32(value, 32)?;
+    let file_type       = read_u32(value, 36)?;
+
+    let flag_defs = [
+        (0x01, "DEBUG"),
+        (0x02, "PRERELEASE"),
+        (0x04, "PATCHED"),
+        (0x08, "PRIVATEBUILD"),
+        (0x10, "INFOINFERRED"),
+        (0x20, "SPECIALBUILD"),
+    ];
+
+    Ok(FixedFileInfo {
+        struct_version: read_u32(value, 4)?,
+        file_version: split(read_u32(value, 8)?, read_u32(value, 12)?),
+        product_version: split(read_u32(value, 16)?, read_u32(value, 20)?),
+        file_flags_mask,
+        file_flags,
+        flags: flag_defs
+            .iter()
+            .filter(|(mask, _)| file_flags & file_flags_mask & mask != 0)
+            .map(|(_, name)| *name)
+            .collect(),
+        file_os,
+        os: match file_os {
+            0x0000_0001 => "DOS",
+            0x0000_0004 => "WINDOWS32",
+            0x0001_0001 => "DOS_WINDOWS16",
+            0x0001_0004 => "DOS_WINDOWS32",
+            0x0004_0000 => "NT",
+            0x0004_0004 => "NT_WINDOWS32",
+            _ => "UNKNOWN",
+        },
+        file_type,
+        kind: match file_type {
+            1 => "APP",
+            2 => "DLL",
+            3 => "DRV",
+            4 => "FONT",
+            5 => "VXD",
+            7 => "STATIC_LIB",
+            _ => "UNKNOWN",
+        },
+        file_subtype: read_u32(value, 40)?,
+        file_date: (read_u32(value, 44)? as u64) << 32 | read_u32(value, 48)? as u64,
+    })
+}
+
+fn format_version(version: [u16; 4]) -> String {
+    format!("{}.{}.{}.{}", version[0], version[1], version[2], version[3])
+}
+
+fn align4(value: usize) -> usize {
+    (value + 3) & !3
+}
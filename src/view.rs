@@ -1,8 +1,26 @@
-use crate::export_table::parse_export_table;
-use crate::export_table::ExportEntry;
-use crate::import_table::ImportEntry;
-use crate::import_table::parse_import_table;
+use crate::exception_table::{parse_exception_table, ExceptionTable};
+use crate::entropy::FileEntropy;
+use crate::export_table::{parse_export_table, parse_exports};
+use crate::export_table::{ExportDirectory, ExportEntry};
+use crate::fingerprint;
+use crate::import_table::{ImportEntry, ImportedDll};
+use crate::import_table::{parse_import_table, parse_imports};
+use crate::anomalies::Anomaly;
+use crate::delay_import_table::{parse_delay_import_table, parse_delay_imports};
+use crate::delay_import_table::{DelayImportEntry, DelayImportedDll};
+use crate::bound_import_table::{parse_bound_imports, BoundImport};
+use crate::certificate_table::{parse_certificate_table, WinCertificate};
+use crate::checksum::Checksum;
+use crate::dotnet::{parse_dotnet, DotNet};
+use crate::debug_directory::{parse_debug_directory, DebugEntry};
+use crate::load_config::{parse_load_config, LoadConfig};
+use crate::overlay::Overlay;
 use crate::parser::PeFile;
+use crate::relocation_table::{parse_relocation_table, RelocationBlock};
+use crate::rich_header::{parse_rich_header, RichHeader};
+use crate::resource_table::{parse_resource_table, ResourceDirectory};
+use crate::tls_directory::{parse_tls_directory, TlsDirectory};
+use crate::version_info::{parse_version_info, VersionInfo};
 use chrono::prelude::DateTime;
 use chrono::Utc;
 use std::time::{UNIX_EPOCH, Duration};
@@ -14,7 +32,7 @@ use crate::utils::{read_u16, format_as_hex};
 /// Wrapper around a parsed PE file, exposing higher-level helper methods.
 /// This allows transforming raw PE data into human-friendly representations.
 pub struct Parsed<'a> {
-    raw: &'a PeFile,
+    raw: &'a PeFile<'a>,
 }
 
 #[derive(Serialize)]
@@ -31,6 +49,8 @@ pub struct ParsedSection {
     pub number_of_linenumbers: u16,
     pub characteristics: String,
     pub flags: Vec<&'static str>,
+    /// Shannon entropy of the raw data, in bits per byte.
+    pub entropy: f64,
 }
 
 #[derive(Serialize)]
@@ -43,8 +63,29 @@ pub struct ParsedPretty {
     pub subsystem: String,
     pub dll_characteristics: Vec<String>,
     pub sections: Vec<ParsedSection>,
+    pub entropy: FileEntropy,
+    pub overlay: Option<Overlay>,
+    pub checksum: Checksum,
+    pub anomalies: Vec<Anomaly>,
+    pub rich_header: Option<RichHeader>,
+    /// Import descriptors, followed by the delay-load ones marked `delay_loaded`.
     pub import_table: Option<Vec<ImportEntry>>,
-    pub export_table: Option<Vec<ExportEntry>>,
+    /// Imported DLLs, delay-loaded ones included, in the same order.
+    pub imports: Option<Vec<ImportedDll>>,
+    pub delay_import_table: Option<Vec<DelayImportEntry>>,
+    pub delay_imports: Option<Vec<DelayImportedDll>>,
+    pub bound_imports: Option<Vec<BoundImport>>,
+    pub export_table: Option<ExportEntry>,
+    pub exports: Option<ExportDirectory>,
+    pub resources: Option<ResourceDirectory>,
+    pub version_info: Option<VersionInfo>,
+    pub relocations: Option<Vec<RelocationBlock>>,
+    pub tls: Option<TlsDirectory>,
+    pub load_config: Option<LoadConfig>,
+    pub debug: Option<Vec<DebugEntry>>,
+    pub exceptions: Option<ExceptionTable>,
+    pub certificates: Option<Vec<WinCertificate>>,
+    pub dotnet: Option<DotNet>,
 }
 
 #[derive(Serialize)]
@@ -55,13 +96,16 @@ pub struct ParsedSummary {
     pub image_base: String,
     pub timestamp: String,
     pub subsystem: String,
+    pub imphash: Option<String>,
+    pub sorted_import_hash: Option<String>,
+    pub exphash: Option<String>,
 }
 
 impl<'a> Parsed<'a> {
     /// Creates a new parsed wrapper instance from a raw PE file reference.
     ///
     /// This enables access to structured views like `pretty_json()` or `summary_json()`.
-    pub fn new(raw: &'a PeFile) -> Self {
+    pub fn new(raw: &'a PeFile<'a>) -> Self {
         Parsed { raw }
     }
 
@@ -70,6 +114,8 @@ impl<'a> Parsed<'a> {
     /// Includes architecture, section info, entry point, characteristics,
     /// and optionally parsed import/export tables. Designed for human inspection.
     pub fn pretty_json(&self) -> ParsedPretty {
+        let delay_import_table = parse_delay_import_table(self.raw).ok();
+        let delay_imports = parse_delay_imports(self.raw).ok();
         ParsedPretty {
             architecture: self.architecture().to_string(),
             entry_point: self.entry_point(),
@@ -82,8 +128,33 @@ impl<'a> Parsed<'a> {
                 .map(|(_, s)| s.to_string())
                 .collect(),
             sections: self.sections(),
-            import_table: parse_import_table(self.raw).ok(),
+            entropy: self.raw.entropy(),
+            overlay: self.raw.overlay(),
+            checksum: self.raw.checksum(),
+            anomalies: self.raw.anomalies(),
+            rich_header: parse_rich_header(self.raw).ok(),
+            import_table: merge_imports(
+                parse_import_table(self.raw).ok(),
+                delay_import_table.as_ref().map(|entries| entries.iter().map(|e| e.to_import_entry(self.raw)).collect()),
+            ),
+            imports: merge_imports(
+                parse_imports(self.raw).ok(),
+                delay_imports.as_ref().map(|dlls| dlls.iter().map(|dll| dll.to_imported_dll(self.raw)).collect()),
+            ),
+            delay_import_table,
+            delay_imports,
+            bound_imports: parse_bound_imports(self.raw).ok(),
             export_table: parse_export_table(self.raw).ok(),
+            exports: parse_exports(self.raw).ok(),
+            resources: parse_resource_table(self.raw).ok(),
+            version_info: self.version_info(),
+            relocations: parse_relocation_table(self.raw).ok(),
+            tls: parse_tls_directory(self.raw).ok(),
+            load_config: parse_load_config(self.raw).ok(),
+            debug: parse_debug_directory(self.raw).ok(),
+            exceptions: parse_exception_table(self.raw).ok(),
+            certificates: parse_certificate_table(self.raw).ok(),
+            dotnet: parse_dotnet(self.raw).ok(),
         }
     }
 
@@ -97,6 +168,9 @@ impl<'a> Parsed<'a> {
             image_base: self.image_base(),
             timestamp: self.timestamp(),
             subsystem: self.subsystem().to_string(),
+            imphash: self.imphash(),
+            sorted_import_hash: self.sorted_import_hash(),
+            exphash: self.exphash(),
         }
     }
 
@@ -186,6 +260,31 @@ impl<'a> Parsed<'a> {
         DateTime::<Utc>::from(d).format("%Y-%m-%d %H:%M:%S").to_string()
     }
 
+    /// Returns the decoded `VS_VERSIONINFO` resource, if the file has one.
+    ///
+    /// Holds the fixed file/product version plus string tables with fields
+    /// like `CompanyName`, `ProductName` and `FileVersion`.
+    pub fn version_info(&self) -> Option<VersionInfo> {
+        parse_version_info(self.raw).ok()
+    }
+
+    /// Returns the pefile/VirusTotal-compatible import hash (MD5, lowercase hex).
+    ///
+    /// `None` if the file has no readable import table.
+    pub fn imphash(&self) -> Option<String> {
+        fingerprint::imphash(self.raw).ok()
+    }
+
+    /// Returns the SHA-256 of the sorted import list, which survives import reordering.
+    pub fn sorted_import_hash(&self) -> Option<String> {
+        fingerprint::sorted_import_hash(self.raw).ok()
+    }
+
+    /// Returns the SHA-256 export hash, or `None` if the file exports nothing.
+    pub fn exphash(&self) -> Option<String> {
+        fingerprint::exphash(self.raw).ok()
+    }
+
     /// Returns the linker version in `vX.Y` format.
     pub fn linker_version(&self) -> String {
         let oh = &self.raw.optional_header;
@@ -332,8 +431,17 @@ impl<'a> Parsed<'a> {
                     number_of_linenumbers: section.number_of_linenumbers,
                     characteristics: format_as_hex(section.characteristics),
                     flags: self.parse_section_flags(&section.characteristics),
+                    entropy: self.raw.section_entropy(section),
                 }
             })
             .collect()
     }
 }
+
+/// Append delay-load imports to the regular ones; `None` only if neither parses.
+fn merge_imports<T>(regular: Option<Vec<T>>, delay: Option<Vec<T>>) -> Option<Vec<T>> {
+    match (regular, delay) {
+        (None, None) => None,
+        (regular, delay) => Some(regular.into_iter().chain(delay).flatten().collect()),
+    }
+}
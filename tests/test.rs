@@ -1,5 +1,24 @@
 use std::path::Path;
-use parsey_rs::{PeFile, Parsed};
+use parsey_rs::{AnomalyKind, Error, Mapping, PeBuffer, PeFile, Parsed, Severity};
+use parsey_rs::debug_directory::{parse_debug_directory, DebugPayload};
+use parsey_rs::exception_table::{parse_exception_table, Arm64Unwind, ExceptionTable};
+use parsey_rs::authentihash::HashAlgorithm;
+use parsey_rs::bound_import_table::parse_bound_imports;
+use parsey_rs::certificate_table::parse_certificate_table;
+use parsey_rs::delay_import_table::parse_delay_imports;
+use parsey_rs::dotnet::{parse_dotnet, MetadataRoot, StreamHeader};
+use parsey_rs::entropy::shannon_entropy;
+use parsey_rs::export_table::parse_exports;
+use parsey_rs::fingerprint::{exphash, imphash, ordinal_name, sorted_import_hash};
+use parsey_rs::import_table::parse_imports;
+use parsey_rs::load_config::parse_load_config;
+use parsey_rs::metadata_tables::parse_metadata_tables;
+use parsey_rs::overlay::{identify, OverlayFormat};
+use parsey_rs::relocation_table::{parse_relocation_table, rebase};
+use parsey_rs::rich_header::parse_rich_header;
+use parsey_rs::resource_table::{parse_resource_table, ResourceId};
+use parsey_rs::tls_directory::parse_tls_directory;
+use parsey_rs::version_info::{Translation, VersionInfo};
 
 #[test]
 fn parse_valid_pe() {
@@ -44,3 +63,1247 @@ fn test_sections_present() {
     assert!(!sections.is_empty());
     assert!(sections.iter().any(|s| s.name == ".text"));
 }
+
+#[test]
+fn test_imports_resolved() {
+    let path = Path::new("tests/test.exe");
+    let pe = PeFile::parse(path).unwrap();
+
+    let imports = parse_imports(&pe).unwrap();
+    assert!(!imports.is_empty());
+
+    let kernel32 = imports
+        .iter()
+        .find(|dll| dll.dll_name.eq_ignore_ascii_case("KERNEL32.dll"))
+        .expect("KERNEL32.dll should be imported");
+    assert!(!kernel32.functions.is_empty());
+
+    // PE32+ thunks are 8 bytes wide, so IAT slots advance by 8.
+    let first = &kernel32.functions[0];
+    assert_eq!(first.iat_rva, kernel32.descriptor.first_thunk);
+    assert_eq!(kernel32.functions[1].iat_rva, first.iat_rva + 8);
+    assert!(kernel32.functions.iter().all(|f| f.name.is_some() || f.ordinal.is_some()));
+}
+
+#[test]
+fn test_imports_survive_bad_thunks() {
+    let mut section = vec![0u8; 0x200];
+    // Two descriptors at RVA 0x1000; the second's thunks run into the end of the file.
+    for (i, (lookup, name, iat)) in [(0x1080u32, 0x1040u32, 0x10C0u32), (0x11F0, 0x1050, 0x11F0)].into_iter().enumerate() {
+        put(&mut section, i * 20, &lookup.to_le_bytes());
+        put(&mut section, i * 20 + 12, &name.to_le_bytes());
+        put(&mut section, i * 20 + 16, &iat.to_le_bytes());
+    }
+    put(&mut section, 0x40, b"a.dll\0");
+    put(&mut section, 0x50, b"b.dll\0");
+    put(&mut section, 0x62, b"Good\0");
+    // a.dll: by name, unmapped hint/name RVA, by ordinal.
+    put(&mut section, 0x80, &0x1060u64.to_le_bytes());
+    put(&mut section, 0x88, &0x7FFF_0000u64.to_le_bytes());
+    put(&mut section, 0x90, &(1u64 << 63 | 5).to_le_bytes());
+    // b.dll: two thunks and no terminator before the end of the file.
+    put(&mut section, 0x1F0, &0x1060u64.to_le_bytes());
+    put(&mut section, 0x1F8, &0x1060u64.to_le_bytes());
+
+    let pe = PeFile::from_vec(build_pe(&[(1, 0x1000, 0x3C)], &section)).unwrap();
+    let imports = parse_imports(&pe).unwrap();
+    assert_eq!(imports.len(), 2);
+
+    let functions = &imports[0].functions;
+    assert_eq!(functions.len(), 3);
+    assert_eq!(functions[0].name.as_deref(), Some("Good"));
+    assert!(functions[1].name.is_none() && functions[1].hint.is_none() && functions[1].ordinal.is_none());
+    assert_eq!(functions[1].iat_rva, 0x10C8);
+    assert_eq!(functions[2].ordinal, Some(5));
+    assert_eq!(imports[1].functions.len(), 2);
+
+    assert_eq!(imphash(&pe).unwrap(), "91bc93a6a84e89fdbdf980e2797cd222");
+
+    // An unterminated run of ordinal thunks is capped.
+    let mut section = vec![0u8; 0x100 + 0x10010 * 8];
+    put(&mut section, 0, &0x1100u32.to_le_bytes());
+    put(&mut section, 12, &0x1040u32.to_le_bytes());
+    put(&mut section, 16, &0x1100u32.to_le_bytes());
+    put(&mut section, 0x40, b"a.dll\0");
+    for slot in section[0x100..].chunks_exact_mut(8) {
+        slot.copy_from_slice(&(1u64 << 63 | 1).to_le_bytes());
+    }
+    let pe = PeFile::from_vec(build_pe(&[(1, 0x1000, 0x28)], &section)).unwrap();
+    assert_eq!(parse_imports(&pe).unwrap()[0].functions.len(), 0x10000);
+}
+
+/// Build a minimal PE32+ image with one section mapped at RVA 0x1000 (file offset 0x400).
+///
+/// `section` becomes the section's raw data, so RVA `0x1000 + n` is byte `n` of it.
+/// `directories` lists `(index, rva, size)` data directory entries to fill in.
+fn build_pe(directories: &[(usize, u32, u32)], section: &[u8]) -> Vec<u8> {
+    let raw_size = section.len().div_ceil(0x200) * 0x200;
+    let mut buf = vec![0u8; 0x400 + raw_size];
+
+    // DOS header
+    buf[0..2].copy_from_slice(b"MZ");
+    buf[0x3C..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+    // PE signature + COFF file header
+    buf[0x40..0x44].copy_from_slice(b"PE\0\0");
+    buf[0x44..0x46].copy_from_slice(&0x8664u16.to_le_bytes());
+    buf[0x46..0x48].copy_from_slice(&1u16.to_le_bytes());
+    buf[0x54..0x56].copy_from_slice(&240u16.to_le_bytes());
+    buf[0x56..0x58].copy_from_slice(&0x2022u16.to_le_bytes());
+    // Optional header (PE32+)
+    let oh = 0x58;
+    buf[oh..oh + 2].copy_from_slice(&0x20Bu16.to_le_bytes());
+    buf[oh + 24..oh + 32].copy_from_slice(&0x1_4000_0000u64.to_le_bytes());
+    buf[oh + 32..oh + 36].copy_from_slice(&0x1000u32.to_le_bytes());
+    buf[oh + 36..oh + 40].copy_from_slice(&0x200u32.to_le_bytes());
+    let size_of_image = 0x1000 + section.len().div_ceil(0x1000).max(1) as u32 * 0x1000;
+    buf[oh + 56..oh + 60].copy_from_slice(&size_of_image.to_le_bytes());
+    buf[oh + 60..oh + 64].copy_from_slice(&0x400u32.to_le_bytes());
+    buf[oh + 68..oh + 70].copy_from_slice(&3u16.to_le_bytes());
+    buf[oh + 108..oh + 112].copy_from_slice(&16u32.to_le_bytes());
+    for &(index, rva, size) in directories {
+        let at = oh + 112 + index * 8;
+        buf[at..at + 4].copy_from_slice(&rva.to_le_bytes());
+        buf[at + 4..at + 8].copy_from_slice(&size.to_le_bytes());
+    }
+    // Section header
+    let sh = oh + 240;
+    buf[sh..sh + 5].copy_from_slice(b".data");
+    buf[sh + 8..sh + 12].copy_from_slice(&(section.len() as u32).to_le_bytes());
+    buf[sh + 12..sh + 16].copy_from_slice(&0x1000u32.to_le_bytes());
+    buf[sh + 16..sh + 20].copy_from_slice(&(raw_size as u32).to_le_bytes());
+    buf[sh + 20..sh + 24].copy_from_slice(&0x400u32.to_le_bytes());
+    buf[sh + 36..sh + 40].copy_from_slice(&0xC000_0040u32.to_le_bytes());
+
+    buf[0x400..0x400 + section.len()].copy_from_slice(section);
+    buf
+}
+
+/// Write `bytes` into `section` at `offset`.
+fn put(section: &mut [u8], offset: usize, bytes: &[u8]) {
+    section[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
+
+/// Section holding an export directory at RVA 0x1010 exporting `Alpha` and a forwarded `Fwd`.
+fn export_section() -> Vec<u8> {
+    let mut section = vec![0u8; 0x200];
+    // IMAGE_EXPORT_DIRECTORY at RVA 0x1010
+    put(&mut section, 0x10 + 12, &0x1080u32.to_le_bytes()); // name
+    put(&mut section, 0x10 + 16, &1u32.to_le_bytes());      // base
+    put(&mut section, 0x10 + 20, &3u32.to_le_bytes());      // number_of_functions
+    put(&mut section, 0x10 + 24, &2u32.to_le_bytes());      // number_of_names
+    put(&mut section, 0x10 + 28, &0x1100u32.to_le_bytes()); // address_of_functions
+    put(&mut section, 0x10 + 32, &0x1120u32.to_le_bytes()); // address_of_names
+    put(&mut section, 0x10 + 36, &0x1130u32.to_le_bytes()); // address_of_name_ordinals
+    put(&mut section, 0x80, b"test.dll\0");
+    put(&mut section, 0x90, b"Alpha\0");
+    put(&mut section, 0xA0, b"Fwd\0");
+    put(&mut section, 0xB0, b"KERNEL32.Sleep\0");
+    // functions: [0x5000, unused, forwarder]
+    put(&mut section, 0x100, &0x5000u32.to_le_bytes());
+    put(&mut section, 0x108, &0x10B0u32.to_le_bytes());
+    put(&mut section, 0x120, &0x1090u32.to_le_bytes());
+    put(&mut section, 0x124, &0x10A0u32.to_le_bytes());
+    put(&mut section, 0x130, &0u16.to_le_bytes());
+    put(&mut section, 0x132, &2u16.to_le_bytes());
+
+    section
+}
+
+#[test]
+fn test_exports_resolved() {
+    let section = export_section();
+    let pe = PeFile::from_vec(build_pe(&[(0, 0x1010, 0x130)], &section)).unwrap();
+    let exports = parse_exports(&pe).unwrap();
+
+    assert_eq!(exports.dll_name, "test.dll");
+    assert_eq!(exports.symbols.len(), 2);
+    assert_eq!(exports.symbols[0].ordinal, 1);
+    assert_eq!(exports.symbols[0].name.as_deref(), Some("Alpha"));
+    assert_eq!(exports.symbols[0].rva, 0x5000);
+    assert!(exports.symbols[0].forwarder.is_none());
+    assert_eq!(exports.symbols[1].ordinal, 3);
+    assert_eq!(exports.symbols[1].name.as_deref(), Some("Fwd"));
+    assert_eq!(exports.symbols[1].forwarder.as_deref(), Some("KERNEL32.Sleep"));
+    assert!(exports.symbols.iter().all(|symbol| symbol.aliases.is_empty()));
+
+    // A third name pointing at function 0 is an alias of Alpha.
+    let mut section = export_section();
+    put(&mut section, 0x10 + 24, &3u32.to_le_bytes());
+    put(&mut section, 0xC0, b"Beta\0");
+    put(&mut section, 0x128, &0x10C0u32.to_le_bytes());
+    put(&mut section, 0x134, &0u16.to_le_bytes());
+    let pe = PeFile::from_vec(build_pe(&[(0, 0x1010, 0x130)], &section)).unwrap();
+    let exports = parse_exports(&pe).unwrap();
+    assert_eq!(exports.symbols.len(), 2);
+    assert_eq!(exports.symbols[0].name.as_deref(), Some("Alpha"));
+    assert_eq!(exports.symbols[0].aliases, ["Beta"]);
+    assert_eq!(exphash(&pe).unwrap(), "22195262739a401e7b9487c1123db5807e462bc0a3bd59b9903b364f07d7a317");
+}
+
+#[test]
+fn test_truncated_headers() {
+    let full = std::fs::read("tests/test.exe").unwrap();
+    let e_lfanew = u32::from_le_bytes(full[0x3C..0x40].try_into().unwrap()) as usize;
+    let optional_header = e_lfanew + 4 + 20;
+    let sections_end = optional_header + 240 + 10 * 40;
+
+    // Cut one byte short of every header boundary.
+    let cuts = [
+        ("empty", 0, 0x40),
+        ("dos", 0x3F, 0x40),
+        ("signature", e_lfanew + 3, e_lfanew + 4),
+        ("file_header", optional_header - 1, optional_header),
+        ("optional_header", optional_header + 111, optional_header + 112),
+        ("data_directories", optional_header + 239, optional_header + 240),
+        ("sections", sections_end - 1, sections_end),
+    ];
+    for (name, len, expected) in cuts {
+        match PeFile::from_bytes(&full[..len]) {
+            Err(Error::InvalidSize { expected: e, found }) => {
+                assert_eq!((e, found), (expected, len), "cut at {name}");
+            }
+            Err(other) => panic!("cut at {name}: unexpected error {other}"),
+            Ok(_) => panic!("cut at {name}: truncated file parsed"),
+        }
+    }
+
+    assert!(PeFile::from_bytes(&full[..sections_end]).is_ok());
+}
+
+#[test]
+fn test_hostile_header_fields() {
+    let mut bytes = std::fs::read("tests/test.exe").unwrap();
+    bytes[0] = b'X';
+    assert!(matches!(PeFile::from_bytes(&bytes), Err(Error::InvalidMagic(_))));
+
+    let mut bytes = std::fs::read("tests/test.exe").unwrap();
+    bytes[0x3C..0x40].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(PeFile::from_bytes(&bytes), Err(Error::InvalidSize { .. })));
+
+    let mut bytes = std::fs::read("tests/test.exe").unwrap();
+    let e_lfanew = u32::from_le_bytes(bytes[0x3C..0x40].try_into().unwrap()) as usize;
+    bytes[e_lfanew + 6..e_lfanew + 8].copy_from_slice(&u16::MAX.to_le_bytes());
+    assert!(matches!(PeFile::from_bytes(&bytes), Err(Error::InvalidSize { .. })));
+}
+
+#[test]
+fn test_in_memory_sources() {
+    let bytes = std::fs::read("tests/test.exe").unwrap();
+    let from_path = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+
+    let from_slice = PeFile::from_bytes(&bytes).unwrap();
+    // An image embedded at an offset is read from the reader's position.
+    let mut archive = vec![0xAAu8; 100];
+    archive.extend_from_slice(&bytes);
+    let mut cursor = std::io::Cursor::new(archive);
+    cursor.set_position(100);
+    let from_reader = PeFile::from_reader(cursor).unwrap();
+    let from_vec = PeFile::from_vec(bytes.clone()).unwrap();
+
+    for pe in [&from_slice, &from_reader, &from_vec] {
+        assert_eq!(*pe.buffer, *from_path.buffer);
+        assert_eq!(pe.sections.len(), from_path.sections.len());
+        assert_eq!(pe.parsed().entry_point(), from_path.parsed().entry_point());
+        assert_eq!(parse_imports(pe).unwrap().len(), parse_imports(&from_path).unwrap().len());
+    }
+}
+
+#[test]
+fn test_borrowed_buffer_is_zero_copy() {
+    let bytes = std::fs::read("tests/test.exe").unwrap();
+    let pe = PeFile::from_bytes(&bytes).unwrap();
+
+    assert!(matches!(pe.buffer, PeBuffer::Borrowed(_)));
+    assert_eq!(pe.buffer.as_ptr(), bytes.as_ptr());
+    assert!(!parse_imports(&pe).unwrap().is_empty());
+
+    let owned = pe.into_owned();
+    assert!(matches!(owned.buffer, PeBuffer::Owned(_)));
+    assert_eq!(*owned.buffer, *bytes);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_mmap_buffer() {
+    let pe = PeFile::from_mmap(Path::new("tests/test.exe")).unwrap();
+    assert!(matches!(pe.buffer, PeBuffer::Mapped(_)));
+    assert!(pe.parsed().sections().iter().any(|s| s.name == ".text"));
+}
+
+#[test]
+fn test_lenient_parsing_collects_anomalies() {
+    let mut bytes = build_pe(&[(1, 0x9000, 0x28)], &[0u8; 0x10]);
+    bytes[0x40] = b'X';                                      // PE signature
+    bytes[0x58..0x5A].copy_from_slice(&0x1234u16.to_le_bytes()); // optional header magic
+    bytes[0x46..0x48].copy_from_slice(&2u16.to_le_bytes());      // two sections, one exists
+
+    assert!(PeFile::from_bytes(&bytes).is_err());
+    let (pe, anomalies) = PeFile::from_bytes_lenient(&bytes).unwrap();
+
+    assert!(pe.optional_header.is_64());
+    assert_eq!(pe.sections.len(), 2);
+    let kinds: Vec<AnomalyKind> = anomalies.iter().map(|a| a.kind).collect();
+    assert!(kinds.contains(&AnomalyKind::BadPeSignature));
+    assert!(kinds.contains(&AnomalyKind::UnsupportedOptionalHeader));
+    assert!(kinds.contains(&AnomalyKind::UnmappedDirectory));
+    assert!(anomalies.iter().any(|a| a.kind == AnomalyKind::BadPeSignature && a.offset == Some(0x40)));
+
+    // Cutting the file inside the section table keeps the headers that fit.
+    let cut = &bytes[..0x58 + 240 + 40 + 10];
+    let (pe, anomalies) = PeFile::from_bytes_lenient(cut).unwrap();
+    assert_eq!(pe.sections.len(), 1);
+    assert!(anomalies.iter().any(|a| a.kind == AnomalyKind::TruncatedSectionTable && a.severity == Severity::Error));
+}
+
+#[test]
+fn test_malformed_directories_reported() {
+    // A relocation block larger than its directory: `relocations` is dropped
+    // from the pretty view, but not silently.
+    let mut section = vec![0u8; 0x20];
+    put(&mut section, 0, &0x1000u32.to_le_bytes());
+    put(&mut section, 4, &0xFFFF_FFF8u32.to_le_bytes());
+    let pe = PeFile::from_vec(build_pe(&[(5, 0x1000, 0x20)], &section)).unwrap();
+    let pretty = Parsed::new(&pe).pretty_json();
+    assert!(pretty.relocations.is_none());
+    let anomaly = pretty.anomalies.iter().find(|a| a.kind == AnomalyKind::MalformedTable).unwrap();
+    assert_eq!(anomaly.offset, Some(0x400));
+    assert!(anomaly.message.starts_with("BASERELOC"));
+
+    for path in ["tests/test.exe", "tests/signed.exe", "tests/dotnet.exe"] {
+        let pe = PeFile::parse(Path::new(path)).unwrap();
+        assert!(pe.anomalies().iter().all(|a| a.kind != AnomalyKind::MalformedTable), "{path}");
+    }
+}
+
+#[test]
+fn test_anomalies_on_valid_file() {
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    assert!(pe.anomalies().iter().all(|a| a.severity < Severity::Error));
+}
+
+#[test]
+fn test_overlapping_sections_reported_once() {
+    // 0xFFFF copies of the same section header: each one overlaps the first.
+    let bytes = build_pe(&[], &[0u8; 0x10]);
+    let table = 0x58 + 240;
+    let header = bytes[table..table + 40].to_vec();
+    let mut many = bytes[..table].to_vec();
+    many[0x46..0x48].copy_from_slice(&0xFFFFu16.to_le_bytes());
+    for _ in 0..0xFFFF {
+        many.extend_from_slice(&header);
+    }
+
+    let pe = PeFile::from_vec(many).unwrap();
+    assert_eq!(pe.sections.len(), 0xFFFF);
+    let overlaps = pe.anomalies().iter().filter(|a| a.kind == AnomalyKind::OverlappingSections).count();
+    assert_eq!(overlaps, 0xFFFE);
+}
+
+#[test]
+fn test_address_translation() {
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+
+    // RVA exactly at a section start (.text: VA 0x11000, raw 0x400).
+    assert_eq!(pe.rva_to_offset(0x11000), Some(0x400));
+    assert_eq!(pe.offset_to_rva(0x400), Some(0x11000));
+    // Headers map 1:1.
+    assert_eq!(pe.map_rva(0x3C), Some(Mapping::File { offset: 0x3C, section: None }));
+    // .textbss has no raw data at all, .data has 0x200 raw bytes of 0x948.
+    assert_eq!(pe.map_rva(0x10000), Some(Mapping::ZeroFill { section: 0 }));
+    assert_eq!(pe.map_rva(0x1D100), Some(Mapping::File { offset: 0xC300, section: Some(3) }));
+    assert_eq!(pe.map_rva(0x1D300), Some(Mapping::ZeroFill { section: 3 }));
+    assert_eq!(pe.rva_to_offset(0x1D300), None);
+    // Past the end of the image.
+    assert_eq!(pe.map_rva(0x10_0000), None);
+
+    let base = pe.optional_header.image_base();
+    assert_eq!(pe.rva_to_va(0x11000), base + 0x11000);
+    assert_eq!(pe.va_to_rva(base + 0x11000), Some(0x11000));
+    assert_eq!(pe.va_to_rva(base - 1), None);
+    assert_eq!(pe.va_to_offset(base + 0x11010), Some(0x410));
+    assert_eq!(pe.offset_to_va(0x410), Some(base + 0x11010));
+    assert_eq!(pe.section_for_rva(0x11010).unwrap().name_string(), ".text");
+
+    // Every data directory now resolves, so the file is clean.
+    assert!(pe.anomalies().is_empty());
+
+    // An unaligned raw pointer is rounded down to 0x200 for both translation
+    // and the section bytes.
+    let mut section = vec![0u8; 0x200];
+    section[0] = 0xCC;
+    let mut bytes = build_pe(&[], &section);
+    bytes[0x58 + 240 + 20..0x58 + 240 + 24].copy_from_slice(&0x410u32.to_le_bytes());
+    let pe = PeFile::from_vec(bytes).unwrap();
+    assert_eq!(pe.rva_to_offset(0x1000), Some(0x400));
+    assert_eq!(pe.section_data(&pe.sections[0])[0], 0xCC);
+    assert_eq!(pe.section_data(&pe.sections[0]).len(), 0x200);
+    assert!(pe.overlay().is_none());
+}
+
+#[test]
+fn test_resource_tree() {
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    let resources = parse_resource_table(&pe).unwrap();
+
+    let manifests = resources.leaves_of_type(24);
+    assert_eq!(manifests.len(), 1);
+    assert_eq!(manifests[0].path, [&ResourceId::Id(24), &ResourceId::Id(1), &ResourceId::Id(1033)]);
+    let bytes = manifests[0].data.bytes(&pe).unwrap();
+    assert_eq!(bytes.len(), manifests[0].data.size as usize);
+    assert!(String::from_utf8_lossy(bytes).contains("assembly"));
+}
+
+#[test]
+fn test_resource_tree_cycle() {
+    let mut section = vec![0u8; 0x100];
+    // Root directory at RVA 0x1010: one named entry and one ID entry, both
+    // pointing back at the root, plus one ID entry pointing at a data leaf.
+    put(&mut section, 0x10 + 12, &1u16.to_le_bytes());
+    put(&mut section, 0x10 + 14, &2u16.to_le_bytes());
+    put(&mut section, 0x10 + 16, &(0x8000_0000u32 | 0x40).to_le_bytes());
+    put(&mut section, 0x10 + 20, &0x8000_0000u32.to_le_bytes());
+    put(&mut section, 0x10 + 24, &7u32.to_le_bytes());
+    put(&mut section, 0x10 + 28, &0x8000_0000u32.to_le_bytes());
+    put(&mut section, 0x10 + 32, &10u32.to_le_bytes());
+    put(&mut section, 0x10 + 36, &0x30u32.to_le_bytes());
+    // Data entry at directory offset 0x30, name string at 0x40.
+    put(&mut section, 0x40, &0x10A0u32.to_le_bytes());
+    put(&mut section, 0x44, &4u32.to_le_bytes());
+    put(&mut section, 0x50, &3u16.to_le_bytes());
+    put(&mut section, 0x52, &[b'A', 0, b'B', 0, b'C', 0]);
+    put(&mut section, 0xA0, b"DATA");
+
+    let pe = PeFile::from_vec(build_pe(&[(2, 0x1010, 0x90)], &section)).unwrap();
+    let resources = parse_resource_table(&pe).unwrap();
+
+    assert_eq!(resources.entries.len(), 1);
+    assert_eq!(resources.entries[0].id, ResourceId::Id(10));
+    let leaves = resources.leaves();
+    assert_eq!(leaves[0].data.bytes(&pe).unwrap(), b"DATA");
+}
+
+/// Encode one version-resource block (`wLength`, `wValueLength`, `wType`, key, value, children).
+fn version_block(key: &str, kind: u16, value: &[u8], value_length: u16, children: &[u8]) -> Vec<u8> {
+    let mut block = vec![0u8; 6];
+    for unit in key.encode_utf16().chain([0]) {
+        block.extend_from_slice(&unit.to_le_bytes());
+    }
+    block.resize(block.len().div_ceil(4) * 4, 0);
+    block.extend_from_slice(value);
+    block.resize(block.len().div_ceil(4) * 4, 0);
+    block.extend_from_slice(children);
+
+    let length = block.len() as u16;
+    block[0..2].copy_from_slice(&length.to_le_bytes());
+    block[2..4].copy_from_slice(&value_length.to_le_bytes());
+    block[4..6].copy_from_slice(&kind.to_le_bytes());
+    block.resize(block.len().div_ceil(4) * 4, 0);
+    block
+}
+
+fn version_string(key: &str, value: &str) -> Vec<u8> {
+    let text: Vec<u8> = value.encode_utf16().chain([0]).flat_map(u16::to_le_bytes).collect();
+    version_block(key, 1, &text, (text.len() / 2) as u16, &[])
+}
+
+#[test]
+fn test_version_info() {
+    let mut fixed = Vec::new();
+    for value in [0xFEEF_04BDu32, 0x0001_0000, 0x0002_0001, 0x0003_0004, 0x0002_0001, 0, 0x3F, 0x02, 0x0004_0004, 2, 0, 0, 0] {
+        fixed.extend_from_slice(&value.to_le_bytes());
+    }
+    let strings = [
+        version_string("CompanyName", "Contoso Ltd."),
+        version_string("ProductName", "Widget"),
+        version_string("FileVersion", "2.1.3.4"),
+    ]
+    .concat();
+    let table = version_block("040904B0", 1, &[], 0, &strings);
+    let string_file_info = version_block("StringFileInfo", 1, &[], 0, &table);
+    let translation = version_block("Translation", 0, &[0x09, 0x04, 0xB0, 0x04], 4, &[]);
+    let var_file_info = version_block("VarFileInfo", 1, &[], 0, &translation);
+    let root = version_block("VS_VERSION_INFO", 0, &fixed, 52, &[string_file_info, var_file_info].concat());
+
+    let info = VersionInfo::parse(&root).unwrap();
+    let fixed = info.fixed.as_ref().unwrap();
+    assert_eq!(fixed.file_version_string(), "2.1.3.4");
+    assert_eq!(fixed.product_version_string(), "2.1.0.0");
+    assert_eq!(fixed.flags, ["PRERELEASE"]);
+    assert_eq!(fixed.kind, "DLL");
+    assert_eq!(fixed.os, "NT_WINDOWS32");
+
+    assert_eq!(info.string_tables[0].language, 0x0409);
+    assert_eq!(info.string_tables[0].code_page, 0x04B0);
+    assert_eq!(info.company_name(), Some("Contoso Ltd."));
+    assert_eq!(info.product_name(), Some("Widget"));
+    assert_eq!(info.file_version(), Some("2.1.3.4"));
+    assert_eq!(info.translations, [Translation { language: 0x0409, code_page: 0x04B0 }]);
+
+    // test.exe carries only a manifest.
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    assert!(pe.parsed().version_info().is_none());
+}
+
+#[test]
+fn test_relocations_and_rebase() {
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    let blocks = parse_relocation_table(&pe).unwrap();
+    assert!(!blocks.is_empty());
+
+    let fixups: Vec<_> = blocks.iter().flat_map(|b| &b.entries).filter(|r| r.type_name != "ABSOLUTE").collect();
+    assert!(!fixups.is_empty());
+    assert!(fixups.iter().all(|r| r.type_name == "DIR64"));
+    assert!(blocks.iter().all(|b| b.entries.iter().all(|r| r.rva & !0xFFF == b.page_rva)));
+
+    let old_base = pe.optional_header.image_base();
+    let new_base = old_base + 0x7FF0_0000;
+    let rebased = PeFile::from_vec(rebase(&pe, new_base).unwrap()).unwrap();
+    assert_eq!(rebased.optional_header.image_base(), new_base);
+
+    for fixup in fixups {
+        let offset = pe.rva_to_offset(fixup.rva).unwrap();
+        let read = |buf: &[u8]| u64::from_le_bytes(buf[offset..offset + 8].try_into().unwrap());
+        assert_eq!(read(&rebased.buffer), read(&pe.buffer).wrapping_add(0x7FF0_0000));
+    }
+
+    // A block claiming to be larger than the directory is rejected up front.
+    let mut section = vec![0u8; 0x20];
+    put(&mut section, 0, &0x1000u32.to_le_bytes());
+    put(&mut section, 4, &0xFFFF_FFF8u32.to_le_bytes());
+    let pe = PeFile::from_vec(build_pe(&[(5, 0x1000, 0x20)], &section)).unwrap();
+    assert!(matches!(parse_relocation_table(&pe), Err(Error::InvalidData)));
+
+    put(&mut section, 4, &0x0Du32.to_le_bytes());
+    let pe = PeFile::from_vec(build_pe(&[(5, 0x1000, 0x20)], &section)).unwrap();
+    assert!(matches!(parse_relocation_table(&pe), Err(Error::InvalidData)));
+}
+
+#[test]
+fn test_tls_callbacks() {
+    let base = 0x1_4000_0000u64;
+    let mut section = vec![0u8; 0x100];
+    // IMAGE_TLS_DIRECTORY64 at RVA 0x1010
+    put(&mut section, 0x10, &(base + 0x1080).to_le_bytes());
+    put(&mut section, 0x18, &(base + 0x1090).to_le_bytes());
+    put(&mut section, 0x20, &(base + 0x10A0).to_le_bytes());
+    put(&mut section, 0x28, &(base + 0x1040).to_le_bytes());
+    // Callback array at RVA 0x1040
+    put(&mut section, 0x40, &(base + 0x10C0).to_le_bytes());
+    put(&mut section, 0x48, &(base + 0x10E0).to_le_bytes());
+
+    let pe = PeFile::from_vec(build_pe(&[(9, 0x1010, 40)], &section)).unwrap();
+    let tls = parse_tls_directory(&pe).unwrap();
+
+    assert_eq!(tls.callbacks_rva, Some(0x1040));
+    assert_eq!(tls.raw_data_rva, Some(0x1080));
+    assert_eq!(tls.index_rva, Some(0x10A0));
+    assert_eq!(tls.callbacks.len(), 2);
+    assert_eq!(tls.callbacks[0].rva, Some(0x10C0));
+    assert_eq!(tls.callbacks[1].va, base + 0x10E0);
+    assert_eq!(tls.callbacks[1].section.as_deref(), Some(".data"));
+
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    assert!(parse_tls_directory(&pe).is_err());
+}
+
+#[test]
+fn test_load_config() {
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    let base = pe.optional_header.image_base();
+    let config = parse_load_config(&pe).unwrap();
+
+    assert_eq!(config.size, 0x140);
+    assert_eq!(config.security_cookie, Some(base + 0x1D000));
+    assert_eq!(config.guard_flags, Some(0x100));
+    assert_eq!(config.guard_flag_names, ["CF_INSTRUMENTED"]);
+    assert!(config.guard_xfg_check_function_pointer.is_some());
+    assert!(config.guard_memcpy_function_pointer.is_some());
+    assert!(config.guard_cf_functions.is_empty());
+
+    // Shrink the declared structure to the Windows 8.1 layout (ends after guard_flags).
+    let mut bytes = pe.buffer.to_vec();
+    let offset = pe.rva_to_offset(pe.optional_header.data_directory()[10].virtual_address).unwrap();
+    bytes[offset..offset + 4].copy_from_slice(&0x94u32.to_le_bytes());
+    let old = PeFile::from_vec(bytes).unwrap();
+    let config = parse_load_config(&old).unwrap();
+    assert_eq!(config.guard_flags, Some(0x100));
+    assert_eq!(config.code_integrity_flags, None);
+    assert_eq!(config.guard_memcpy_function_pointer, None);
+}
+
+#[test]
+fn test_debug_directory() {
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    let entries = parse_debug_directory(&pe).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].type_name, "CODEVIEW");
+    match &entries[0].payload {
+        Some(DebugPayload::CodeView(cv)) => {
+            assert_eq!(cv.signature, "RSDS");
+            assert_eq!(cv.guid.as_deref(), Some("9E7BEE7E-F9B9-49CC-97F4-159DE6679924"));
+            assert_eq!(cv.age, 1);
+            assert!(cv.path.ends_with("test.pdb"));
+            assert_eq!(cv.symbol_store_path, "test.pdb/9E7BEE7EF9B949CC97F4159DE66799241/test.pdb");
+        }
+        other => panic!("expected CodeView, got {other:?}"),
+    }
+    assert!(matches!(&entries[1].payload, Some(DebugPayload::VcFeature(f)) if f.sdl == 1));
+
+    let mut section = vec![0u8; 0x100];
+    // Three IMAGE_DEBUG_DIRECTORY entries at RVA 0x1000, located by RVA only.
+    for (i, (kind, size, rva)) in [(13u32, 0x28u32, 0x1060u32), (16, 8, 0x10A0), (20, 4, 0x10B0)].into_iter().enumerate() {
+        let at = i * 28;
+        put(&mut section, at + 12, &kind.to_le_bytes());
+        put(&mut section, at + 16, &size.to_le_bytes());
+        put(&mut section, at + 20, &rva.to_le_bytes());
+    }
+    // POGO: signature, then (rva, size, padded name) records.
+    put(&mut section, 0x60, &0x5047_5500u32.to_le_bytes());
+    put(&mut section, 0x64, &0x1000u32.to_le_bytes());
+    put(&mut section, 0x68, &0x20u32.to_le_bytes());
+    put(&mut section, 0x6C, b".text$mn\0");
+    put(&mut section, 0x78, &0x2000u32.to_le_bytes());
+    put(&mut section, 0x7C, &0x10u32.to_le_bytes());
+    put(&mut section, 0x80, b".rdata\0");
+    // REPRO: length-prefixed hash.
+    put(&mut section, 0xA0, &4u32.to_le_bytes());
+    put(&mut section, 0xA4, &[0xDE, 0xAD, 0xBE, 0xEF]);
+    // EX_DLLCHARACTERISTICS: CET_COMPAT
+    put(&mut section, 0xB0, &1u32.to_le_bytes());
+
+    let pe = PeFile::from_vec(build_pe(&[(6, 0x1000, 3 * 28)], &section)).unwrap();
+    let entries = parse_debug_directory(&pe).unwrap();
+    match &entries[0].payload {
+        Some(DebugPayload::Pogo(pogo)) => {
+            assert_eq!(pogo.signature, "PGU");
+            let names: Vec<_> = pogo.entries.iter().map(|e| e.name.as_str()).collect();
+            assert_eq!(names, [".text$mn", ".rdata"]);
+            assert_eq!(pogo.entries[1].rva, 0x2000);
+        }
+        other => panic!("expected POGO, got {other:?}"),
+    }
+    assert!(matches!(&entries[1].payload, Some(DebugPayload::Repro(r)) if r.hash == "deadbeef"));
+    assert!(matches!(&entries[2].payload, Some(DebugPayload::ExDllCharacteristics(e)) if e.names == ["CET_COMPAT"]));
+}
+
+#[test]
+fn test_exception_table() {
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    let table = parse_exception_table(&pe).unwrap();
+    let ExceptionTable::X64(functions) = &table else { panic!("expected x64 table") };
+    assert_eq!(functions.len(), 128);
+    assert_eq!(table.function_ranges()[0], (0x118A0, 0x11C51));
+
+    let unwind = functions[0].unwind_info.as_ref().unwrap();
+    assert_eq!(unwind.flag_names, ["EHANDLER", "UHANDLER"]);
+    assert_eq!(unwind.frame_register, Some("RBP"));
+    assert_eq!(unwind.frame_offset, 0x20);
+    let ops: Vec<_> = unwind.codes.iter().map(|code| code.op_name).collect();
+    assert_eq!(ops, ["SET_FPREG", "ALLOC_LARGE", "PUSH_NONVOL", "PUSH_NONVOL"]);
+    assert_eq!(unwind.codes[1].value, Some(0x1A8));
+    assert_eq!(unwind.codes[2].register, Some("RDI"));
+    assert!(unwind.handler_rva.is_some());
+    assert!(functions.iter().any(|f| f.unwind_info.as_ref().is_some_and(|u| u.chained.is_some())));
+
+    let mut section = vec![0u8; 0x100];
+    // Packed entry: 64-byte function, 32-byte frame, two saved integer registers.
+    let packed: u32 = 1 | 0x10 << 2 | 2 << 16 | 3 << 21 | 2 << 23;
+    put(&mut section, 0x00, &0x1100u32.to_le_bytes());
+    put(&mut section, 0x04, &packed.to_le_bytes());
+    // Unpacked entry pointing at .xdata at RVA 0x1040.
+    put(&mut section, 0x08, &0x1200u32.to_le_bytes());
+    put(&mut section, 0x0C, &0x1040u32.to_le_bytes());
+    // 128-byte function, X bit, one epilog scope, one code word.
+    let header: u32 = 0x20 | 1 << 20 | 1 << 22 | 1 << 27;
+    put(&mut section, 0x40, &header.to_le_bytes());
+    put(&mut section, 0x44, &(0x1Cu32 | 2 << 22).to_le_bytes());
+    put(&mut section, 0x48, &[0x81, 0x02, 0xE4, 0xE3]);
+    put(&mut section, 0x4C, &0x1800u32.to_le_bytes());
+
+    let mut bytes = build_pe(&[(3, 0x1000, 16)], &section);
+    bytes[0x44..0x46].copy_from_slice(&0xAA64u16.to_le_bytes());
+    let pe = PeFile::from_vec(bytes).unwrap();
+    let table = parse_exception_table(&pe).unwrap();
+    assert_eq!(table.function_ranges(), [(0x1100, 0x1140), (0x1200, 0x1280)]);
+
+    let ExceptionTable::Arm64(functions) = &table else { panic!("expected ARM64 table") };
+    match &functions[0].unwind {
+        Some(Arm64Unwind::Packed(packed)) => {
+            assert_eq!((packed.reg_i, packed.cr, packed.frame_size), (2, 3, 32));
+        }
+        other => panic!("expected packed unwind, got {other:?}"),
+    }
+    match &functions[1].unwind {
+        Some(Arm64Unwind::Unpacked(xdata)) => {
+            assert_eq!(xdata.epilog_scopes.len(), 1);
+            assert_eq!(xdata.epilog_scopes[0].start_offset, 0x70);
+            assert_eq!(xdata.epilog_scopes[0].start_index, 2);
+            let names: Vec<_> = xdata.codes.iter().map(|code| code.name).collect();
+            assert_eq!(names, ["save_fplr_x", "alloc_s", "end", "nop"]);
+            assert_eq!(xdata.handler_rva, Some(0x1800));
+            assert_eq!(xdata.handler_data_rva, Some(0x1050));
+        }
+        other => panic!("expected .xdata unwind, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_delay_imports() {
+    let base = 0x40_0000u32;
+    let mut section = vec![0u8; 0x200];
+    // RVA-based ImgDelayDescr at RVA 0x1000
+    put(&mut section, 0x00, &1u32.to_le_bytes());
+    put(&mut section, 0x04, &0x10A0u32.to_le_bytes());
+    put(&mut section, 0x08, &0x10E0u32.to_le_bytes());
+    put(&mut section, 0x0C, &0x10C0u32.to_le_bytes());
+    put(&mut section, 0x10, &0x1060u32.to_le_bytes());
+    // Old VA-based ImgDelayDescr at RVA 0x1020
+    put(&mut section, 0x24, &(base + 0x10B0).to_le_bytes());
+    put(&mut section, 0x28, &(base + 0x10E8).to_le_bytes());
+    put(&mut section, 0x2C, &(base + 0x10D0).to_le_bytes());
+    put(&mut section, 0x30, &(base + 0x1080).to_le_bytes());
+    // Name tables: one by name plus one by ordinal, then a VA-based name thunk.
+    put(&mut section, 0x60, &0x1100u64.to_le_bytes());
+    put(&mut section, 0x68, &(1u64 << 63 | 5).to_le_bytes());
+    put(&mut section, 0x80, &(base as u64 + 0x1110).to_le_bytes());
+    put(&mut section, 0xA0, b"a.dll\0");
+    put(&mut section, 0xB0, b"b.dll\0");
+    put(&mut section, 0x100, &7u16.to_le_bytes());
+    put(&mut section, 0x102, b"Foo\0");
+    put(&mut section, 0x110, &9u16.to_le_bytes());
+    put(&mut section, 0x112, b"Bar\0");
+
+    let mut bytes = build_pe(&[(13, 0x1000, 0x60)], &section);
+    // Low image base so the old-style 32-bit VAs can address the image.
+    bytes[0x58 + 24..0x58 + 32].copy_from_slice(&(base as u64).to_le_bytes());
+    let pe = PeFile::from_vec(bytes).unwrap();
+    let dlls = parse_delay_imports(&pe).unwrap();
+    assert_eq!(dlls.len(), 2);
+
+    assert!(dlls[0].descriptor.is_rva_based());
+    assert_eq!(dlls[0].dll_name, "a.dll");
+    assert_eq!(dlls[0].module_handle_rva, Some(0x10E0));
+    assert_eq!(dlls[0].functions.len(), 2);
+    assert_eq!(dlls[0].functions[0].name.as_deref(), Some("Foo"));
+    assert_eq!(dlls[0].functions[0].hint, Some(7));
+    assert_eq!(dlls[0].functions[1].ordinal, Some(5));
+    assert_eq!(dlls[0].functions[1].iat_rva, 0x10C8);
+
+    assert!(!dlls[1].descriptor.is_rva_based());
+    assert_eq!(dlls[1].dll_name, "b.dll");
+    assert_eq!(dlls[1].iat_rva, Some(0x10D0));
+    assert_eq!(dlls[1].module_handle_rva, Some(0x10E8));
+    assert_eq!(dlls[1].functions[0].name.as_deref(), Some("Bar"));
+    assert_eq!(dlls[1].functions[0].iat_rva, 0x10D0);
+
+    // The pretty view lists delay-loaded DLLs with the regular imports.
+    let pretty = Parsed::new(&pe).pretty_json();
+    let table = pretty.import_table.unwrap();
+    assert!(table.iter().all(|entry| entry.delay_loaded));
+    assert_eq!((table[1].name, table[1].original_first_thunk, table[1].first_thunk), (0x10B0, 0x1080, 0x10D0));
+    let imports = pretty.imports.unwrap();
+    let names: Vec<_> = imports.iter().map(|dll| dll.dll_name.as_str()).collect();
+    assert_eq!(names, ["a.dll", "b.dll"]);
+    assert_eq!(imports[0].functions.len(), 2);
+    assert_eq!(pretty.delay_imports.unwrap().len(), 2);
+
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    assert!(parse_delay_imports(&pe).is_err());
+    let table = Parsed::new(&pe).pretty_json().import_table.unwrap();
+    assert_eq!(table.len(), parse_imports(&pe).unwrap().len());
+    assert!(table.iter().all(|entry| !entry.delay_loaded));
+}
+
+#[test]
+fn test_bound_imports() {
+    let mut section = vec![0u8; 0x100];
+    // Two import descriptors: new-style binding, and an old-style stamp that went stale.
+    for (at, stamp, name_rva) in [(0x00, 0xFFFF_FFFFu32, 0x10A0u32), (0x14, 0x1234, 0x10B0)] {
+        put(&mut section, at, &0x1080u32.to_le_bytes());
+        put(&mut section, at + 4, &stamp.to_le_bytes());
+        put(&mut section, at + 12, &name_rva.to_le_bytes());
+        put(&mut section, at + 16, &0x1080u32.to_le_bytes());
+    }
+    put(&mut section, 0xA0, b"a.dll\0");
+    put(&mut section, 0xB0, b"b.dll\0");
+
+    let mut bytes = build_pe(&[(1, 0x1000, 0x3C), (11, 0x300, 0x40)], &section);
+    // Bound import directory in the headers, names relative to its start.
+    let bound = [
+        &0xAAAAu32.to_le_bytes()[..], &0x20u16.to_le_bytes(), &1u16.to_le_bytes(),
+        &0xBBBBu32.to_le_bytes(), &0x26u16.to_le_bytes(), &0u16.to_le_bytes(),
+        &0x5678u32.to_le_bytes(), &0x30u16.to_le_bytes(), &0u16.to_le_bytes(),
+        &[0u8; 8], b"a.dll\0", b"ntdll.dll\0", b"B.DLL\0",
+    ]
+    .concat();
+    bytes[0x300..0x300 + bound.len()].copy_from_slice(&bound);
+
+    let pe = PeFile::from_vec(bytes).unwrap();
+    let entries = parse_bound_imports(&pe).unwrap();
+    assert_eq!(entries.len(), 2);
+
+    assert_eq!(entries[0].module_name, "a.dll");
+    assert_eq!(entries[0].time_date_stamp, 0xAAAA);
+    assert_eq!(entries[0].forwarders.len(), 1);
+    assert_eq!(entries[0].forwarders[0].module_name, "ntdll.dll");
+    assert_eq!(entries[0].forwarders[0].time_date_stamp, 0xBBBB);
+    assert!(entries[0].consistent);
+
+    assert_eq!(entries[1].module_name, "B.DLL");
+    assert_eq!(entries[1].import_time_date_stamp, Some(0x1234));
+    assert!(!entries[1].consistent);
+}
+
+#[test]
+fn test_authenticode_signature() {
+    let pe = PeFile::parse(Path::new("tests/signed.exe")).unwrap();
+    let certificates = parse_certificate_table(&pe).unwrap();
+    assert_eq!(certificates.len(), 1);
+    assert_eq!(certificates[0].offset, 0x10C00);
+    assert_eq!(certificates[0].revision_name, "REVISION_2_0");
+    assert_eq!(certificates[0].type_name, "PKCS_SIGNED_DATA");
+
+    let signature = certificates[0].signature.as_ref().unwrap();
+    assert_eq!(signature.content_type, "SPC_INDIRECT_DATA");
+    assert_eq!(signature.digest_algorithms, ["sha256"]);
+    let indirect = signature.indirect_data.as_ref().unwrap();
+    assert_eq!(indirect.data_type, "SPC_PE_IMAGE_DATA");
+    assert_eq!(indirect.digest_algorithm, "sha256");
+    assert_eq!(indirect.digest, "333D18A42DDBD02E3B3800DA2B28C2A5A5B71884B277218967161F8BE0CB3DE7");
+
+    assert_eq!(signature.certificates.len(), 2);
+    let leaf = &signature.certificates[0];
+    assert_eq!(leaf.subject, "C=US, O=Parsey, CN=Parsey Test Signer");
+    assert_eq!(leaf.issuer, "C=US, O=Parsey, CN=Parsey Test Root CA");
+    assert_eq!(leaf.serial_number, "0A1B2C3D");
+    assert_eq!(leaf.not_before, "2024-01-01 00:00:00");
+    assert_eq!(leaf.not_after, "2034-01-01 00:00:00");
+    assert_eq!(leaf.signature_algorithm, "sha256WithRSAEncryption");
+
+    let signer = &signature.signers[0];
+    assert_eq!(signature.signer_certificate(signer).map(|c| c.serial_number.as_str()), Some("0A1B2C3D"));
+    assert_eq!(signer.digest_algorithm, "sha256");
+    assert_eq!(signer.program_name.as_deref(), Some("Parsey Test"));
+    assert_eq!(signer.more_info_url.as_deref(), Some("https://example.com"));
+    assert!(signer.message_digest.is_some());
+
+    // RFC 3161 timestamp
+    let timestamp = &signer.timestamps[0];
+    let info = timestamp.timestamp_info.as_ref().unwrap();
+    assert_eq!(info.time, "2025-04-20 12:00:00");
+    assert_eq!(info.serial_number, "1234");
+    assert_eq!(timestamp.certificates[0].subject, "C=US, O=Parsey, CN=Parsey Test TSA");
+
+    // Nested SHA-1 signature with a legacy countersignature
+    let nested = &signer.nested_signatures[0];
+    let nested_digest = nested.indirect_data.as_ref().unwrap();
+    assert_eq!(nested_digest.digest_algorithm, "sha1");
+    assert_eq!(nested_digest.digest, "0A23E6738B4E40BAFD25ABB58847FA89C4207DEE");
+    let counter = &nested.signers[0].counter_signatures[0];
+    assert_eq!(counter.signing_time.as_deref(), Some("2025-04-20 12:00:00"));
+    assert_eq!(counter.serial_number.as_deref(), Some("2002"));
+
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    assert!(parse_certificate_table(&pe).is_err());
+}
+
+#[test]
+fn test_authentihash() {
+    let pe = PeFile::parse(Path::new("tests/signed.exe")).unwrap();
+    assert_eq!(pe.authentihash(HashAlgorithm::Sha256).unwrap(), "333D18A42DDBD02E3B3800DA2B28C2A5A5B71884B277218967161F8BE0CB3DE7");
+    assert_eq!(pe.authentihash(HashAlgorithm::Sha1).unwrap(), "0A23E6738B4E40BAFD25ABB58847FA89C4207DEE");
+
+    // Primary SHA-256 signature, then the nested SHA-1 one.
+    let checks = pe.verify_authentihash().unwrap();
+    let algorithms: Vec<_> = checks.iter().map(|check| check.digest_algorithm.as_str()).collect();
+    assert_eq!(algorithms, ["sha256", "sha1"]);
+    assert!(checks.iter().all(|check| check.matches));
+
+    // The checksum field is excluded from the hash...
+    let mut bytes = pe.buffer.to_vec();
+    let checksum = pe.e_lfanew + 4 + 20 + 64;
+    bytes[checksum] ^= 0xFF;
+    let patched = PeFile::from_vec(bytes.clone()).unwrap();
+    assert!(patched.verify_authentihash().unwrap().iter().all(|check| check.matches));
+
+    // ...but section data is not.
+    bytes[0x400] ^= 0xFF;
+    let tampered = PeFile::from_vec(bytes).unwrap();
+    assert!(tampered.verify_authentihash().unwrap().iter().all(|check| !check.matches));
+
+    // Unsigned files still hash (same image bytes), but have nothing to verify.
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    assert_eq!(pe.authentihash(HashAlgorithm::Sha256).unwrap(), "333D18A42DDBD02E3B3800DA2B28C2A5A5B71884B277218967161F8BE0CB3DE7");
+    assert!(pe.verify_authentihash().is_err());
+}
+
+#[test]
+fn test_checksum() {
+    let pe = PeFile::parse(Path::new("tests/signed.exe")).unwrap();
+    let checksum = pe.checksum();
+    assert_eq!(checksum.stored, 0x18112);
+    assert_eq!(checksum.computed, 0x18112);
+    assert!(checksum.valid && !checksum.enforced);
+
+    // A zero checksum is normal for user-mode images.
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    assert_eq!(pe.checksum().stored, 0);
+    assert!(!pe.checksum().valid);
+    assert!(pe.anomalies().iter().all(|a| a.kind != AnomalyKind::ChecksumMismatch));
+
+    // A stale checksum is informational for user-mode images...
+    let signed = PeFile::parse(Path::new("tests/signed.exe")).unwrap();
+    let mut bytes = signed.buffer.to_vec();
+    bytes[0x400] ^= 0xFF;
+    let tampered = PeFile::from_vec(bytes.clone()).unwrap();
+    assert!(!tampered.checksum().valid);
+    let mismatch = tampered.anomalies().into_iter().find(|a| a.kind == AnomalyKind::ChecksumMismatch).unwrap();
+    assert_eq!(mismatch.severity, Severity::Info);
+    assert_eq!(mismatch.offset, Some(signed.e_lfanew + 4 + 20 + 64));
+
+    // ...but fatal for drivers.
+    let subsystem = signed.e_lfanew + 4 + 20 + 68;
+    bytes[subsystem..subsystem + 2].copy_from_slice(&1u16.to_le_bytes());
+    let driver = PeFile::from_vec(bytes).unwrap();
+    assert!(driver.checksum().enforced);
+    assert!(driver.anomalies().iter().any(|a| a.kind == AnomalyKind::ChecksumMismatch && a.severity == Severity::Error));
+}
+
+#[test]
+fn test_dotnet_header() {
+    let pe = PeFile::parse(Path::new("tests/dotnet.exe")).unwrap();
+    let dotnet = parse_dotnet(&pe).unwrap();
+
+    let header = &dotnet.header;
+    assert_eq!(header.size, 72);
+    assert_eq!((header.major_runtime_version, header.minor_runtime_version), (2, 5));
+    assert_eq!(header.flag_names, ["ILONLY"]);
+    assert_eq!(header.entry_point_token, Some(0x0600_0001));
+    assert_eq!(header.entry_point_rva, None);
+    assert_eq!(header.metadata.virtual_address, 0x27080);
+    assert_eq!(header.strong_name_signature.size, 0);
+
+    let metadata = &dotnet.metadata;
+    assert_eq!(metadata.version, "v4.0.30319");
+    assert_eq!((metadata.major_version, metadata.minor_version), (1, 1));
+    let names: Vec<_> = metadata.streams.iter().map(|stream| stream.name.as_str()).collect();
+    assert_eq!(names, ["#~", "#Strings", "#US", "#GUID", "#Blob"]);
+    let guid = metadata.stream("#GUID").unwrap();
+    assert_eq!(guid.size, 16);
+    assert_eq!(guid.data(&pe).unwrap()[..4], [0x1F, 0x2E, 0x3D, 0x4C]);
+    assert_eq!(metadata.stream("#~").unwrap().rva, 0x27080 + 108);
+
+    assert_eq!(dotnet.vtable_fixups.len(), 1);
+    let fixup = &dotnet.vtable_fixups[0];
+    assert_eq!(fixup.type_names, ["64BIT", "FROM_UNMANAGED"]);
+    assert_eq!(fixup.tokens, [0x0600_0001]);
+
+    assert!(Parsed::new(&pe).pretty_json().dotnet.is_some());
+    assert!(pe.anomalies().iter().all(|a| a.severity < Severity::Error));
+
+    // Native images have no CLR header.
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    assert!(parse_dotnet(&pe).is_err());
+}
+
+#[test]
+fn test_dotnet_metadata_tables() {
+    let pe = PeFile::parse(Path::new("tests/dotnet.exe")).unwrap();
+    let tables = parse_dotnet(&pe).unwrap().tables.unwrap();
+    assert_eq!((tables.major_version, tables.minor_version, tables.heap_sizes), (2, 0, 0));
+    let rows: Vec<_> = tables.tables.iter().map(|table| (table.name, table.rows)).collect();
+    assert_eq!(rows, [
+        ("Module", 1), ("TypeRef", 3), ("TypeDef", 2), ("Field", 1), ("MethodDef", 3), ("Param", 2),
+        ("MemberRef", 3), ("CustomAttribute", 1), ("ModuleRef", 1), ("ImplMap", 1), ("Assembly", 1),
+        ("AssemblyRef", 1), ("ManifestResource", 1),
+    ]);
+
+    let module = tables.module.as_ref().unwrap();
+    assert_eq!(module.name, "hello.exe");
+    assert_eq!(module.mvid.as_deref(), Some("4C3D2E1F-6A5B-8879-0123-456789ABCDEF"));
+
+    let type_names: Vec<_> = tables.type_defs.iter().map(|t| (t.namespace.as_str(), t.name.as_str())).collect();
+    assert_eq!(type_names, [("", "<Module>"), ("Hello", "Program")]);
+    assert_eq!(tables.type_defs[1].extends.as_deref(), Some("System.Object"));
+    assert_eq!(tables.type_refs[1].name, "Console");
+    assert_eq!(tables.type_refs[1].resolution_scope, 0x2300_0001);
+
+    let methods: Vec<_> = tables.methods.iter().map(|m| (m.name.as_str(), m.declaring_type.as_deref())).collect();
+    assert_eq!(methods, [
+        ("Main", Some("Hello.Program")),
+        (".ctor", Some("Hello.Program")),
+        ("MessageBoxW", Some("Hello.Program")),
+    ]);
+    assert_eq!(tables.methods[0].rva, 0x27050);
+    assert_eq!(tables.fields[0].name, "greeting");
+    assert_eq!(tables.params[0].name, "args");
+
+    assert_eq!(tables.member_refs[0].parent_name.as_deref(), Some("System.Console"));
+    assert_eq!(tables.member_refs[0].name, "WriteLine");
+    let attribute = &tables.custom_attributes[0];
+    assert_eq!((attribute.parent, attribute.constructor), (0x0600_0001, 0x0A00_0003));
+    assert_eq!(attribute.attribute_type.as_deref(), Some("System.STAThreadAttribute"));
+    assert_eq!(attribute.value_size, 4);
+
+    // P/Invoke targets.
+    assert_eq!(tables.module_refs, ["user32.dll"]);
+    let pinvoke = &tables.impl_maps[0];
+    assert_eq!((pinvoke.member.as_str(), pinvoke.import_name.as_str(), pinvoke.module.as_str()), ("MessageBoxW", "MessageBoxW", "user32.dll"));
+
+    let assembly = tables.assembly.as_ref().unwrap();
+    assert_eq!((assembly.name.as_str(), assembly.version.as_str()), ("hello", "1.2.3.4"));
+    assert_eq!(assembly.public_key, "");
+    let reference = &tables.assembly_refs[0];
+    assert_eq!((reference.name.as_str(), reference.version.as_str()), ("mscorlib", "4.0.0.0"));
+    assert_eq!(reference.public_key_or_token, "B77A5C561934E089");
+
+    assert_eq!(tables.manifest_resources[0].name, "Hello.Strings.resources");
+    assert_eq!(tables.manifest_resources[0].implementation, None);
+}
+
+/// Append each `(value, width)` column of a metadata table row to `out`.
+fn push_row(out: &mut Vec<u8>, columns: &[(u32, usize)]) {
+    for &(value, width) in columns {
+        out.extend_from_slice(&value.to_le_bytes()[..width]);
+    }
+}
+
+#[test]
+fn test_metadata_tables_wide_indexes() {
+    // heap_sizes 0x07 makes every heap index 4 bytes. 0x10000 ModuleRef rows widen
+    // simple indexes into it; 0x4000 TypeSpec rows widen TypeDefOrRef (2 tag bits)
+    // and MemberRefParent (3 tag bits) coded indexes.
+    let mut tables = Vec::new();
+    tables.extend_from_slice(&[0, 0, 0, 0, 2, 0, 0x07, 1]);
+    let valid = [0x00u8, 0x02, 0x06, 0x0A, 0x1A, 0x1B, 0x1C].iter().fold(0u64, |mask, &id| mask | 1 << id);
+    tables.extend_from_slice(&valid.to_le_bytes());
+    tables.extend_from_slice(&0u64.to_le_bytes());
+    for rows in [1u32, 1, 1, 1, 0x1_0000, 0x4000, 1] {
+        tables.extend_from_slice(&rows.to_le_bytes());
+    }
+    // Module, TypeDef (extends itself), MethodDef, MemberRef (parent TypeSpec 0x3000).
+    push_row(&mut tables, &[(0, 2), (0x1_0000, 4), (1, 4), (0, 4), (0, 4)]);
+    push_row(&mut tables, &[(0, 4), (0x1_0009, 4), (0, 4), (1 << 2, 4), (1, 2), (1, 2)]);
+    push_row(&mut tables, &[(0, 4), (0, 2), (0x2096, 2), (0x1_0010, 4), (0x1_0000, 4), (1, 2)]);
+    push_row(&mut tables, &[(0x3000 << 3 | 4, 4), (0x1_0027, 4), (0x1_0000, 4)]);
+    // ModuleRef: only the last row is named. TypeSpec: empty signatures.
+    tables.resize(tables.len() + 0xFFFF * 4, 0);
+    push_row(&mut tables, &[(0x1_001C, 4)]);
+    tables.resize(tables.len() + 0x4000 * 4, 0);
+    // ImplMap: MethodDef 1 imported from ModuleRef 0x10000.
+    push_row(&mut tables, &[(0x0104, 2), (1 << 1 | 1, 2), (0x1_0010, 4), (0x1_0000, 4)]);
+
+    let mut strings = vec![0u8; 0x1_0000];
+    strings.extend_from_slice(b"wide.dll\0Widget\0MessageBoxW\0user32.dll\0Invoke\0\0");
+    let guids = vec![0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF];
+    let mut blobs = vec![0u8; 0x1_0000];
+    blobs.extend_from_slice(&[3, 1, 2, 3]);
+
+    let mut section = Vec::new();
+    let mut streams = Vec::new();
+    for (name, data) in [("#~", &tables), ("#Strings", &strings), ("#GUID", &guids), ("#Blob", &blobs)] {
+        let offset = section.len() as u32;
+        section.extend_from_slice(data);
+        section.resize(section.len().div_ceil(4) * 4, 0);
+        streams.push(StreamHeader { name: name.to_string(), offset, size: data.len() as u32, rva: 0x1000 + offset });
+    }
+    let pe = PeFile::from_vec(build_pe(&[], &section)).unwrap();
+    let metadata = MetadataRoot {
+        rva: 0x1000,
+        major_version: 1,
+        minor_version: 1,
+        version: "v4.0.30319".to_string(),
+        flags: 0,
+        streams,
+    };
+    let tables = parse_metadata_tables(&pe, &metadata).unwrap();
+
+    assert_eq!(tables.heap_sizes, 0x07);
+    let rows: Vec<_> = tables.tables.iter().map(|t| (t.name, t.rows)).collect();
+    assert_eq!(rows[4..], [("ModuleRef", 0x1_0000), ("TypeSpec", 0x4000), ("ImplMap", 1)]);
+
+    let module = tables.module.unwrap();
+    assert_eq!(module.name, "wide.dll");
+    assert_eq!(module.mvid.as_deref(), Some("00112233-4455-6677-8899-AABBCCDDEEFF"));
+    assert_eq!(tables.type_defs[0].name, "Widget");
+    assert_eq!(tables.type_defs[0].extends.as_deref(), Some("Widget"));
+    assert_eq!((tables.type_defs[0].field_list, tables.type_defs[0].method_list), (1, 1));
+    assert_eq!(tables.methods[0].name, "MessageBoxW");
+    assert_eq!(tables.methods[0].declaring_type.as_deref(), Some("Widget"));
+    assert_eq!(tables.methods[0].param_list, 1);
+    assert_eq!(tables.member_refs[0].parent, 0x1B00_3000);
+    assert_eq!(tables.member_refs[0].name, "Invoke");
+    assert_eq!(tables.module_refs.len(), 0x1_0000);
+    assert_eq!(tables.module_refs.last().map(String::as_str), Some("user32.dll"));
+    let import = &tables.impl_maps[0];
+    assert_eq!((import.mapping_flags, import.member.as_str()), (0x0104, "MessageBoxW"));
+    assert_eq!((import.import_name.as_str(), import.module.as_str()), ("MessageBoxW", "user32.dll"));
+}
+
+#[test]
+fn test_rich_header() {
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    let rich = parse_rich_header(&pe).unwrap();
+    assert_eq!((rich.offset, rich.size), (0x80, 0x60));
+    assert_eq!(rich.key, 0x6D87_AC13);
+    assert!(rich.valid);
+    assert_eq!(rich.hash, "33a65ecf4275ca65601f288f5a2c1015");
+
+    let entries: Vec<_> = rich.entries.iter().map(|e| (e.product_id, e.build, e.count)).collect();
+    assert_eq!(entries, [
+        (0x103, 34321, 3), (0x104, 34321, 11), (0x105, 34321, 25), (0x101, 34321, 7),
+        (0x101, 30795, 4), (0x001, 0, 85), (0x105, 34433, 1), (0x0FF, 34433, 1), (0x102, 34433, 1),
+    ]);
+    let linker = rich.entries.last().unwrap();
+    assert_eq!(linker.product, Some("Linker1400"));
+    assert_eq!(linker.tool, Some("Linker"));
+    assert_eq!(linker.visual_studio, Some("Visual Studio 2022"));
+    assert_eq!(rich.entries[0].tool, Some("MASM"));
+    assert_eq!(rich.entries[5].product, Some("Import0"));
+    assert_eq!(rich.entries[5].visual_studio, None);
+
+    // Editing the DOS stub breaks the checksum but not the decoding.
+    let mut bytes = pe.buffer.to_vec();
+    bytes[0x50] ^= 0xFF;
+    let patched = PeFile::from_vec(bytes).unwrap();
+    let rich = parse_rich_header(&patched).unwrap();
+    assert!(!rich.valid);
+    assert_eq!(rich.entries.len(), 9);
+
+    // Without the `Rich` marker there is no header to find.
+    let mut bytes = pe.buffer.to_vec();
+    bytes[0xD8..0xDC].copy_from_slice(&[0; 4]);
+    assert!(parse_rich_header(&PeFile::from_vec(bytes).unwrap()).is_err());
+}
+
+#[test]
+fn test_import_fingerprints() {
+    let mut section = vec![0u8; 0x200];
+    // Two import descriptors at RVA 0x1010, then the null terminator.
+    put(&mut section, 0x10, &0x1100u32.to_le_bytes());
+    put(&mut section, 0x10 + 12, &0x1080u32.to_le_bytes());
+    put(&mut section, 0x10 + 16, &0x1100u32.to_le_bytes());
+    put(&mut section, 0x24, &0x1140u32.to_le_bytes());
+    put(&mut section, 0x24 + 12, &0x1090u32.to_le_bytes());
+    put(&mut section, 0x24 + 16, &0x1140u32.to_le_bytes());
+    put(&mut section, 0x80, b"WS2_32.dll\0");
+    put(&mut section, 0x90, b"OLEAUT32.dll\0");
+    put(&mut section, 0xA2, b"connect\0");
+    // ws2_32: ordinal 115 (WSAStartup), unknown ordinal 300, then `connect` by name.
+    put(&mut section, 0x100, &(1u64 << 63 | 115).to_le_bytes());
+    put(&mut section, 0x108, &(1u64 << 63 | 300).to_le_bytes());
+    put(&mut section, 0x110, &0x10A0u64.to_le_bytes());
+    // oleaut32: ordinal 2 (SysAllocString).
+    put(&mut section, 0x140, &(1u64 << 63 | 2).to_le_bytes());
+
+    let pe = PeFile::from_vec(build_pe(&[(1, 0x1010, 0x3C)], &section)).unwrap();
+    // MD5("ws2_32.wsastartup,ws2_32.ord300,ws2_32.connect,oleaut32.sysallocstring")
+    assert_eq!(imphash(&pe).unwrap(), "6d67ab7104386308d60e6898647870b1");
+    assert_eq!(sorted_import_hash(&pe).unwrap(), "11be265da2f5b5603c0b104f33050eb3e7a094dcd85c3fc360b51492c916a13b");
+    assert!(exphash(&pe).is_err());
+
+    assert_eq!(ordinal_name("WSOCK32.DLL", 3), Some("closesocket"));
+    assert_eq!(ordinal_name("oleaut32.dll", 424), Some("OleLoadPicturePath"));
+    assert_eq!(ordinal_name("kernel32.dll", 1), None);
+
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    let summary = Parsed::new(&pe).summary_json();
+    assert_eq!(summary.imphash.as_deref(), Some("20c750c76c4205378fe496453b8ddc37"));
+    assert_eq!(summary.sorted_import_hash.as_deref(), Some("b754844a3b7d112521ddb9e9d47afff507e69d6106acbbc2b6c6c3913ff323bb"));
+    assert_eq!(summary.exphash, None);
+
+    // SHA-256("alpha,fwd")
+    let pe = PeFile::from_vec(build_pe(&[(0, 0x1010, 0x130)], &export_section())).unwrap();
+    assert_eq!(Parsed::new(&pe).exphash().as_deref(), Some("cc68dd15f1d6153fa6fe3b4d004c54c179b3ef54d95abc31ba2e25d26e4e51dc"));
+}
+
+#[test]
+fn test_entropy() {
+    assert_eq!(shannon_entropy(&[]), 0.0);
+    assert_eq!(shannon_entropy(&[0x41; 64]), 0.0);
+    assert_eq!(shannon_entropy(&(0..=255).collect::<Vec<u8>>()), 8.0);
+
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    let entropy = pe.entropy();
+    assert!((entropy.file - 3.716389).abs() < 1e-6);
+    assert!((entropy.headers - 3.095688).abs() < 1e-6);
+    assert_eq!(entropy.overlay, None);
+
+    let sections = Parsed::new(&pe).sections();
+    assert_eq!(sections[0].name, ".textbss");
+    assert_eq!(sections[0].entropy, 0.0);
+    assert_eq!(sections[1].name, ".text");
+    assert!((sections[1].entropy - 3.734448).abs() < 1e-6);
+
+    // The sliding histogram agrees with recomputing each window.
+    let profile = pe.entropy_profile(4096, 2048);
+    // Overlapping windows stop at the first one reaching the end of the file.
+    assert_eq!(profile.len(), (pe.buffer.len() - 4096).div_ceil(2048) + 1);
+    assert!(profile[..profile.len() - 1].iter().all(|window| window.size == 4096));
+    for window in &profile {
+        let data = &pe.buffer[window.offset..window.offset + window.size];
+        assert!((window.entropy - shannon_entropy(data)).abs() < 1e-9);
+    }
+    let last = profile.last().unwrap();
+    assert_eq!(last.offset + last.size, pe.buffer.len());
+    assert_eq!(pe.entropy_profile(256, 1024).len(), pe.buffer.len().div_ceil(1024));
+    assert!(pe.entropy_profile(0, 16).is_empty());
+
+    // Oversized windows and steps clamp to the file instead of overflowing.
+    let whole = pe.entropy_profile(usize::MAX, 1);
+    assert_eq!(whole.len(), 1);
+    assert_eq!((whole[0].offset, whole[0].size), (0, pe.buffer.len()));
+    assert!((whole[0].entropy - pe.entropy().file).abs() < 1e-9);
+    assert_eq!(pe.entropy_profile(64, usize::MAX).len(), 1);
+    assert_eq!(pe.entropy_profile(usize::MAX, usize::MAX).len(), 1);
+
+    // Data appended after the last section is the overlay.
+    let mut bytes = pe.buffer.to_vec();
+    bytes.extend((0..=255u8).cycle().take(1024));
+    let pe = PeFile::from_vec(bytes).unwrap();
+    assert_eq!(pe.entropy().overlay, Some(8.0));
+}
+
+#[test]
+fn test_overlay() {
+    let pe = PeFile::parse(Path::new("tests/test.exe")).unwrap();
+    assert!(pe.overlay().is_none());
+    assert!(pe.overlay_data().is_none());
+    assert!(Parsed::new(&pe).pretty_json().overlay.is_none());
+
+    // Another PE appended after the last section.
+    let mut bytes = pe.buffer.to_vec();
+    bytes.extend_from_slice(&pe.buffer);
+    let dropper = PeFile::from_vec(bytes).unwrap();
+    let overlay = dropper.overlay().unwrap();
+    assert_eq!((overlay.offset, overlay.size), (0x10C00, 0x10C00));
+    assert_eq!(overlay.format, Some(OverlayFormat::Pe));
+    assert_eq!(overlay.md5, "27107d6c5833e61fe2e1d8a797b245ab");
+    assert_eq!(overlay.sha256, "ffabec2619d8f7cae5824fc5f3d6d882ba262ee75efa6fdfb436ba4f62d0c044");
+    assert_eq!(overlay.sha1.len(), 40);
+    assert!((overlay.entropy - pe.entropy().file).abs() < 1e-9);
+    assert_eq!(dropper.overlay_data().unwrap(), &pe.buffer[..]);
+
+    // The certificate table of a signed file is not overlay...
+    let signed = PeFile::parse(Path::new("tests/signed.exe")).unwrap();
+    assert!(signed.overlay().is_none());
+    assert_eq!(signed.entropy().overlay, None);
+
+    // ...but data appended after it is.
+    let mut bytes = signed.buffer.to_vec();
+    let cert_end = bytes.len();
+    bytes.extend_from_slice(b"PK\x03\x04payload");
+    let appended = PeFile::from_vec(bytes).unwrap();
+    let overlay = appended.overlay().unwrap();
+    assert_eq!((overlay.offset, overlay.size), (cert_end, 11));
+    assert_eq!(overlay.format, Some(OverlayFormat::Zip));
+
+    assert_eq!(identify(b"7z\xBC\xAF\x27\x1C\0\x04"), Some(OverlayFormat::SevenZip));
+    assert_eq!(identify(b"MSCF\0\0\0\0"), Some(OverlayFormat::Cab));
+    assert_eq!(identify(b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1"), Some(OverlayFormat::Ole));
+    assert_eq!(identify(b"\0\0\0\0\xEF\xBE\xAD\xDENullsoftInst"), Some(OverlayFormat::Nsis));
+    assert_eq!(identify(b"rDlPtS02\x87eVx"), Some(OverlayFormat::InnoSetup));
+    assert_eq!(identify(b"\x78\x9Ccompressed...MEI\x0C\x0B\x0A\x0B\x0E\0\0"), Some(OverlayFormat::PyInstaller));
+    assert_eq!(identify(b"MZ not really a PE"), None);
+    assert_eq!(identify(&[]), None);
+}